@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use spellout::{Code, Codes, CodesBuilder, Matching, PhoneticCode};
+use std::hint::black_box;
+
+const TEXT: &str = "The quick brown fox jumps over the lazy dog 0123456789. ";
+
+/// Finds the code by scanning all entries, the way of looking up before the indexes were introduced.
+fn linear_code(codes: &Codes, c: char) -> Option<&Code> {
+    let upper = c.to_uppercase().to_string();
+    codes.entries().find(|code| code.letters() == upper)
+}
+
+fn linear_decode(codes: &Codes, items: &[String]) -> String {
+    items.iter()
+        .map(|item| codes.entries().find(|code| code.spellings().any(|s| s == item))
+            .map(|code| code.letters().to_string())
+            .unwrap_or_else(|| " ".to_string()))
+        .collect()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let text = TEXT.repeat(100);
+    let mut group = c.benchmark_group("encode");
+    for pc in [PhoneticCode::Nato, PhoneticCode::Japanese] {
+        let codes = CodesBuilder::try_build(pc.clone()).unwrap();
+        group.bench_function(format!("indexed/{pc}"), |b| {
+            b.iter(|| codes.encode(black_box(&text)).filter(|(_, code)| code.is_some()).count())
+        });
+        group.bench_function(format!("linear/{pc}"), |b| {
+            b.iter(|| black_box(&text).chars().filter(|c| linear_code(&codes, *c).is_some()).count())
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for pc in [PhoneticCode::Nato, PhoneticCode::Japanese] {
+        let codes = CodesBuilder::try_build(pc.clone()).unwrap();
+        let spellings = codes.entries().map(|code| code.code().to_string()).collect::<Vec<_>>();
+        let items = spellings.iter().cycle().take(5000).cloned().collect::<Vec<_>>();
+        group.bench_function(format!("indexed/{pc}"), |b| {
+            b.iter(|| codes.decode_with(black_box(items.clone()), Matching::Exact))
+        });
+        group.bench_function(format!("linear/{pc}"), |b| {
+            b.iter(|| linear_decode(&codes, black_box(&items)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_build(c: &mut Criterion) {
+    c.bench_function("build/nato", |b| b.iter(|| CodesBuilder::try_build(black_box(PhoneticCode::Nato)).unwrap()));
+    c.bench_function("codes_of/nato", |b| b.iter(|| spellout::codes_of(black_box(PhoneticCode::Nato)).entries().count()));
+}
+
+fn bench_extend(c: &mut Criterion) {
+    let japanese = CodesBuilder::try_build(PhoneticCode::Japanese).unwrap();
+    let overrides = vec![Code::try_new("あ", "愛のア").unwrap(), Code::try_new("ア", "愛のア").unwrap()].into_iter().collect::<Codes>();
+    c.bench_function("extend/japanese", |b| b.iter(|| {
+        let mut codes = japanese.clone();
+        codes.extend(black_box(overrides.clone()));
+        codes
+    }));
+    c.bench_function("collect/japanese", |b| b.iter(|| japanese.entries().cloned().collect::<Codes>()));
+}
+
+criterion_group!(benches, bench_encode, bench_decode, bench_build, bench_extend);
+criterion_main!(benches);
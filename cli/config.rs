@@ -0,0 +1,316 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use clap::{parser::ValueSource, ArgMatches, ValueEnum};
+use serde::Deserialize;
+use spellout::{CasePolicy, Error};
+
+use crate::{output::OutputFormat, parse_code, AlphabetOpts, Command, InlineOpts};
+
+/// The defaults of the command line options, given by the configuration file, the profiles, and the `SPELLOUT_*` environment variables.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Settings {
+    code: Option<String>,
+    case: Option<String>,
+    no_separators: Option<bool>,
+    only_code: Option<bool>,
+    format: Option<String>,
+    inline: Option<bool>,
+    letter_separator: Option<String>,
+    word_separator: Option<String>,
+    unknown: Option<String>,
+}
+
+/// The configuration file, the top level settings and the named profiles in `[profiles.<NAME>]` tables.
+#[derive(Debug, Default)]
+struct Config {
+    defaults: Settings,
+    profiles: HashMap<String, Settings>,
+}
+
+impl Config {
+    fn read(path: &Path) -> Result<Config, Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::FileNotFound(path.display().to_string(), Some(e)))?;
+        Config::parse(&content, &path.display().to_string())
+    }
+
+    /// Parses the content of the configuration file, `origin` is the name of the file in the error messages.
+    fn parse(content: &str, origin: &str) -> Result<Config, Error> {
+        let invalid = |e: toml::de::Error| Error::Parse(format!("{origin}: {}", e.message()));
+        let mut table = toml::from_str::<toml::Table>(content).map_err(invalid)?;
+        let profiles = match table.remove("profiles") {
+            Some(profiles) => profiles.try_into().map_err(invalid)?,
+            None => HashMap::new(),
+        };
+        let defaults = toml::Value::Table(table).try_into().map_err(invalid)?;
+        Ok(Config { defaults, profiles })
+    }
+
+    /// Returns the settings in order of the precedence, the environment variables, the profile, and the top level.
+    /// The profile is given by `--profile` or `$SPELLOUT_PROFILE`, and `env` looks up the environment variables.
+    /// The resolved settings must not enable the conflicting outputs, same as the command line options.
+    fn settings(mut self, profile: Option<String>, env: impl Fn(&str) -> Option<String>) -> Result<Settings, Error> {
+        let mut settings = std::mem::take(&mut self.defaults);
+        if let Some(name) = profile.or_else(|| env("SPELLOUT_PROFILE")) {
+            let Some(selected) = self.profiles.remove(&name) else {
+                let mut names = self.profiles.into_keys().collect::<Vec<_>>();
+                names.sort();
+                return Err(Error::Parse(format!("{name}: unknown profile (available: {})", names.join(", "))));
+            };
+            settings = settings.overlay(selected);
+        }
+        settings.overlay(Settings::from_env(&env)?).validate()
+    }
+}
+
+/// Returns the path of the configuration file, `$SPELLOUT_CONFIG`, `$XDG_CONFIG_HOME/spellout/config.toml`,
+/// or `$HOME/.config/spellout/config.toml`, and whether the path is given explicitly.
+fn config_path() -> Option<(PathBuf, bool)> {
+    if let Some(path) = std::env::var_os("SPELLOUT_CONFIG") {
+        return Some((PathBuf::from(path), true));
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| (dir.join("spellout").join("config.toml"), false))
+}
+
+/// Loads the settings of the configuration file, the profile, and the environment variables (see [`Config::settings`]).
+pub(crate) fn load(profile: Option<String>) -> Result<Settings, Error> {
+    let config = match config_path() {
+        Some((path, explicit)) if explicit || path.exists() => Config::read(&path)?,
+        _ => Config::default(),
+    };
+    config.settings(profile, |key| std::env::var(key).ok())
+}
+
+impl Settings {
+    /// Returns the settings of the `SPELLOUT_<KEY>` variables, which `env` looks up.
+    fn from_env(env: &impl Fn(&str) -> Option<String>) -> Result<Settings, Error> {
+        let var = |key: &str| env(&format!("SPELLOUT_{key}"));
+        let flag = |key: &str| var(key).map(|value| parse_bool(key, &value)).transpose();
+        Ok(Settings {
+            code: var("CODE"),
+            case: var("CASE"),
+            no_separators: flag("NO_SEPARATORS")?,
+            only_code: flag("ONLY_CODE")?,
+            format: var("FORMAT"),
+            inline: flag("INLINE")?,
+            letter_separator: var("LETTER_SEPARATOR"),
+            word_separator: var("WORD_SEPARATOR"),
+            unknown: var("UNKNOWN"),
+        })
+    }
+
+    /// Returns the settings overridden by the given ones.
+    fn overlay(self, other: Settings) -> Settings {
+        Settings {
+            code: other.code.or(self.code),
+            case: other.case.or(self.case),
+            no_separators: other.no_separators.or(self.no_separators),
+            only_code: other.only_code.or(self.only_code),
+            format: other.format.or(self.format),
+            inline: other.inline.or(self.inline),
+            letter_separator: other.letter_separator.or(self.letter_separator),
+            word_separator: other.word_separator.or(self.word_separator),
+            unknown: other.unknown.or(self.unknown),
+        }
+    }
+
+    /// Rejects the outputs which conflict with each other, `inline`, `only-code`, and `format`.
+    fn validate(self) -> Result<Settings, Error> {
+        let outputs = [
+            ("inline", self.inline == Some(true)),
+            ("only-code", self.only_code == Some(true)),
+            ("format", self.format.is_some()),
+        ];
+        let enabled = outputs.iter().filter(|(_, enabled)| *enabled).map(|(key, _)| *key).collect::<Vec<_>>();
+        if enabled.len() > 1 {
+            return Err(Error::Parse(format!("{}: conflicting settings, disable all but one of them", enabled.join(", "))));
+        }
+        Ok(self)
+    }
+
+    /// Applies the settings to the options of the command, which are not given in the command line.
+    pub(crate) fn apply(&self, command: &mut Command, matches: &ArgMatches) -> Result<(), Error> {
+        match command {
+            Command::Encode(opts) => {
+                self.apply_alphabet(&mut opts.alphabet, matches)?;
+                let layout = !is_given(matches, "only") && !is_given(matches, "format");
+                self.apply_inline(&mut opts.inline, matches, layout);
+                if !["only", "format", "inline"].iter().any(|id| is_given(matches, id)) {
+                    opts.only = self.only_code.unwrap_or(opts.only);
+                    opts.format = self.output_format()?.or(opts.format);
+                }
+            },
+            Command::Decode(opts) => {
+                self.apply_alphabet(&mut opts.alphabet, matches)?;
+                self.apply_inline(&mut opts.inline, matches, true);
+                if !is_given(matches, "format") {
+                    opts.format = self.output_format()?.or(opts.format);
+                }
+            },
+            Command::Show(opts) => {
+                self.apply_alphabet(&mut opts.alphabet, matches)?;
+                if !is_given(matches, "only") && !is_given(matches, "format") {
+                    opts.only = self.only_code.unwrap_or(opts.only);
+                }
+            },
+            Command::List(_) | Command::Check(_) | Command::Convert(_) => {},
+        }
+        Ok(())
+    }
+
+    fn apply_alphabet(&self, opts: &mut AlphabetOpts, matches: &ArgMatches) -> Result<(), Error> {
+        if let Some(code) = self.code.as_deref().filter(|_| !is_given(matches, "code") && !is_given(matches, "input")) {
+            opts.code = parse_code(code).map_err(Error::Parse)?;
+        }
+        if let Some(case) = self.case.as_deref().filter(|_| !is_given(matches, "case")) {
+            opts.case = CasePolicy::from_str(case, true)
+                .map_err(|_| Error::Parse(format!("{case}: unknown case policy (unicode, ascii, or turkish)")))?;
+        }
+        if !is_given(matches, "no_separators") {
+            opts.no_separators = self.no_separators.unwrap_or(opts.no_separators);
+        }
+        Ok(())
+    }
+
+    /// Applies the layout of inline mode, `enabled` is `false` if the other output is given in the command line.
+    fn apply_inline(&self, opts: &mut InlineOpts, matches: &ArgMatches, enabled: bool) {
+        if enabled && !is_given(matches, "inline") {
+            opts.inline = self.inline.unwrap_or(opts.inline);
+        }
+        opts.letter_separator = opts.letter_separator.take().or_else(|| self.letter_separator.clone());
+        opts.word_separator = opts.word_separator.take().or_else(|| self.word_separator.clone());
+        opts.unknown = opts.unknown.take().or_else(|| self.unknown.clone());
+    }
+
+    fn output_format(&self) -> Result<Option<OutputFormat>, Error> {
+        self.format.as_deref()
+            .map(|format| OutputFormat::from_str(format, true)
+                .map_err(|_| Error::Parse(format!("{format}: unknown output format (json, ndjson, csv, or tsv)"))))
+            .transpose()
+    }
+}
+
+/// Returns `true` if the argument is given in the command line, the unknown ids (e.g., in the other commands) are not given.
+fn is_given(matches: &ArgMatches, id: &str) -> bool {
+    matches.try_contains_id(id).unwrap_or(false)
+        && matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, Error> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "" | "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(Error::Parse(format!("SPELLOUT_{key}: expected a boolean, found '{value}'"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli_command, normalize_args, CliOpts, CodeSpec, DecodeOpts, EncodeOpts};
+    use clap::FromArgMatches;
+
+    const CONFIG: &str = r#"
+code = "uk"
+only-code = true
+unknown = "*"
+
+[profiles.radio]
+code = "international"
+case = "ascii"
+"#;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |key| vars.iter().find(|(name, _)| *name == key).map(|(_, value)| value.to_string())
+    }
+
+    /// Parses the arguments, and applies the settings to the command.
+    fn apply(settings: &Settings, args: &[&str]) -> Command {
+        let command = cli_command();
+        let matches = command.clone().try_get_matches_from(normalize_args(args.iter().map(Into::into), &command)).unwrap();
+        let mut command = CliOpts::from_arg_matches(&matches).unwrap().command.unwrap();
+        let (_, command_matches) = matches.subcommand().unwrap();
+        settings.apply(&mut command, command_matches).unwrap();
+        command
+    }
+
+    fn code_of(command: &Command) -> Option<&str> {
+        match command {
+            Command::Encode(EncodeOpts { alphabet: AlphabetOpts { code: CodeSpec::Code(name), .. }, .. })
+            | Command::Decode(DecodeOpts { alphabet: AlphabetOpts { code: CodeSpec::Code(name), .. }, .. }) => Some(name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(CONFIG, "config.toml").unwrap();
+        assert_eq!(config.defaults.code.as_deref(), Some("uk"));
+        assert_eq!(config.defaults.only_code, Some(true));
+        assert_eq!(config.profiles["radio"].case.as_deref(), Some("ascii"));
+
+        let Err(Error::Parse(message)) = Config::parse("cod = \"uk\"", "config.toml") else {
+            panic!("the unknown key should be reported");
+        };
+        assert!(message.starts_with("config.toml: unknown field `cod`"), "{message}");
+        assert!(Config::parse("[profiles.radio]\nformat = 1", "config.toml").is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let settings = Config::parse(CONFIG, "config.toml").unwrap().settings(None, env(&[])).unwrap();
+        assert_eq!((settings.code.as_deref(), settings.case.as_deref()), (Some("uk"), None));
+
+        let settings = Config::parse(CONFIG, "config.toml").unwrap().settings(Some("radio".to_string()), env(&[])).unwrap();
+        assert_eq!((settings.code.as_deref(), settings.case.as_deref()), (Some("international"), Some("ascii")));
+        assert_eq!(settings.unknown.as_deref(), Some("*")); // the top level is the default of the profile.
+
+        let settings = Config::parse(CONFIG, "config.toml").unwrap().settings(None, env(&[("SPELLOUT_PROFILE", "radio")])).unwrap();
+        assert_eq!(settings.code.as_deref(), Some("international"));
+
+        let Err(Error::Parse(message)) = Config::parse(CONFIG, "config.toml").unwrap().settings(Some("tv".to_string()), env(&[])) else {
+            panic!("the unknown profile should be reported");
+        };
+        assert_eq!(message, "tv: unknown profile (available: radio)");
+    }
+
+    #[test]
+    fn test_precedence() {
+        let vars = [("SPELLOUT_PROFILE", "radio"), ("SPELLOUT_CODE", "nato"), ("SPELLOUT_ONLY_CODE", "no")];
+        let settings = Config::parse(CONFIG, "config.toml").unwrap().settings(None, env(&vars)).unwrap();
+        assert_eq!((settings.code.as_deref(), settings.only_code), (Some("nato"), Some(false)));
+        assert!(Settings::from_env(&env(&[("SPELLOUT_INLINE", "maybe")])).is_err());
+
+        let command = apply(&settings, &["spellout", "encode", "hi"]);
+        assert_eq!(code_of(&command), Some("nato"));
+        let Command::Encode(opts) = &command else { unreachable!() };
+        assert_eq!((opts.only, opts.alphabet.case, opts.inline.unknown.as_deref()), (false, CasePolicy::Ascii, Some("*")));
+
+        let command = apply(&settings, &["spellout", "--profile", "radio", "encode", "--code", "uk", "--case", "unicode", "--only-code", "hi"]);
+        assert_eq!(code_of(&command), Some("uk"));
+        let Command::Encode(opts) = &command else { unreachable!() };
+        assert_eq!((opts.only, opts.alphabet.case), (true, CasePolicy::Unicode));
+
+        let command = apply(&settings, &["spellout", "decode", "--unknown", "_", "Alfa"]);
+        let Command::Decode(opts) = &command else { unreachable!() };
+        assert_eq!(opts.inline.unknown.as_deref(), Some("_"));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let config = format!("{CONFIG}\n[profiles.inline]\ninline = true\n");
+        let Err(Error::Parse(message)) = Config::parse(&config, "config.toml").unwrap().settings(Some("inline".to_string()), env(&[])) else {
+            panic!("the inline profile over only-code should be rejected");
+        };
+        assert_eq!(message, "inline, only-code: conflicting settings, disable all but one of them");
+        let vars = [("SPELLOUT_ONLY_CODE", "no")];
+        assert!(Config::parse(&config, "config.toml").unwrap().settings(Some("inline".to_string()), env(&vars)).is_ok());
+        let vars = [("SPELLOUT_ONLY_CODE", "no"), ("SPELLOUT_INLINE", "1"), ("SPELLOUT_FORMAT", "json")];
+        assert!(Config::parse(CONFIG, "config.toml").unwrap().settings(None, env(&vars)).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use std::path::Path;
 
 #[cfg(debug_assertions)]
 mod generator {
-    use clap::{Command, CommandFactory};
+    use clap::Command;
     use clap_complete::Shell;
     use std::fs::File;
     use std::path::Path;
@@ -20,7 +20,7 @@ mod generator {
         use Shell::{Bash, Elvish, Fish, PowerShell, Zsh};
         let appname = "spellout";
 
-        let mut app = crate::CliOpts::command();
+        let mut app = crate::cli_command();
         app.set_bin_name(appname);
 
         generate_impl(Bash, &mut app, appname, outdir, format!("bash/{appname}"));
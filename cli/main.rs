@@ -1,94 +1,395 @@
-use std::{io::BufRead, path::PathBuf};
+use std::{io::BufRead, path::{Path, PathBuf}, sync::OnceLock};
 
-use clap::{Parser, ValueEnum};
-use spellout::{Codes, CodesBuilder, Error, PhoneticCode};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use spellout::{CasePolicy, Code, Codes, DecodeOptions, DecodeReport, Diagnostic, Error, ExportFormat, InlineOptions, Matching, Registry, Separators};
+
+use output::{Output, OutputFormat};
+
+mod config;
+mod output;
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version, about, long_about = None, args_conflicts_with_subcommands = true,
+    override_usage = "spellout <COMMAND>\n       spellout [ARGS]...",
+    after_help = "Without the command, the arguments are encoded in the same manner as the `encode` command.
+The flag forms (`--list`, `--print`, and `--decode`) are deprecated, use the commands instead."
+)]
 struct CliOpts {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    legacy: LegacyOpts,
+
     #[arg(
-        short, long, value_enum, hide_default_value = true, hide_possible_values = true, 
-        default_value_t = PhoneticCode::Nato,
+        long, global = true, value_name = "NAME",
+        help = "Specify the profile in the configuration file ($XDG_CONFIG_HOME/spellout/config.toml)."
+    )]
+    profile: Option<String>,
+
+    #[cfg(debug_assertions)]
+    #[arg(long, hide = true, default_value_t = false, help = "Generates completion files.")]
+    gencomp: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(about = "Encodes the words into the phonetic codes.")]
+    Encode(EncodeOpts),
+    #[command(about = "Decodes the phonetic codes into the string.")]
+    Decode(DecodeOpts),
+    #[command(about = "Lists the available phonetic codes.")]
+    List(ListOpts),
+    #[command(about = "Shows the phonetic codes of the alphabet.")]
+    Show(ShowOpts),
+    #[command(about = "Checks the custom phonetic code file, and reports the problems with the line and the column.")]
+    Check(CheckOpts),
+    #[command(about = "Converts the custom phonetic code file into the other format.")]
+    Convert(ConvertOpts),
+}
+
+/// The options to build the alphabet, shared by the commands.
+#[derive(Args, Debug)]
+struct AlphabetOpts {
+    #[arg(
+        short, long, hide_default_value = true,
+        default_value = "nato",
         value_parser = parse_code,
         help = "Specify the phonetic code for encoding/decoding the input text.
-Default is NATO. Use `--list` option to see all available codes."
+Default is NATO, or `code` in the configuration file. Use `list` command to see all available codes.
+Gives `auto` to detect the phonetic code in decoding."
+    )]
+    code: CodeSpec,
+
+    #[arg(
+        long, value_enum, default_value_t = CasePolicy::Unicode,
+        help = "Specify the case folding policy for matching the letters."
+    )]
+    case: CasePolicy,
+
+    #[arg(
+        long = "no-separators", default_value_t = false,
+        help = "Does not spell the word separators and punctuations (e.g., \"Space\" and \"Stop\")."
+    )]
+    no_separators: bool,
+
+    #[arg(long, value_name = "FILE", help = "Specify the path to a custom phonetic code file.
+The files in the search path (e.g., ~/.local/share/spellout/codes) are available by `--code` instead.")]
+    input: Option<PathBuf>,
+}
+
+/// The options of the single line layout, shared by `encode` and `decode` commands.
+#[derive(Args, Debug, Default)]
+struct InlineOpts {
+    #[arg(
+        long, default_value_t = false,
+        help = "Spells the codes in a single line, such as \"Hotel Echo / Whiskey Oscar\" (or decodes that layout)."
+    )]
+    inline: bool,
+
+    #[arg(long, value_name = "SEP", requires = "inline", help = "Specify the separator between the codes in inline mode. Default is a space.")]
+    letter_separator: Option<String>,
+
+    #[arg(long, value_name = "SEP", requires = "inline", help = "Specify the separator between the words in inline mode. Default is \" / \".")]
+    word_separator: Option<String>,
+
+    #[arg(long, value_name = "MARKER", help = "Specify the marker for the characters without the phonetic code in encoding, and for the unrecognized codes in decoding.
+Default is \"?\" in encoding in inline mode, and blank (a space in the decoded text) otherwise.")]
+    unknown: Option<String>,
+}
+
+impl InlineOpts {
+    /// Returns the layout, or `None` if inline mode is disabled.
+    fn options(&self) -> Option<InlineOptions> {
+        if !self.inline {
+            return None;
+        }
+        let mut options = InlineOptions::default();
+        if let Some(separator) = &self.letter_separator {
+            options = options.letter_separator(separator);
+        }
+        if let Some(separator) = &self.word_separator {
+            options = options.word_separator(separator);
+        }
+        if let Some(marker) = &self.unknown {
+            options = options.unknown(marker);
+        }
+        Some(options)
+    }
+}
+
+#[derive(Args, Debug)]
+struct EncodeOpts {
+    #[command(flatten)]
+    alphabet: AlphabetOpts,
+
+    #[command(flatten)]
+    inline: InlineOpts,
+
+    #[arg(
+        long = "only-code", default_value_t = false, conflicts_with = "inline",
+        help = "Prints the only phonetic code for the given words, in the case of the letters (e.g., \"hotel\" for 'h'), which `decode` decodes into the original text."
+    )]
+    only: bool,
+
+    #[arg(
+        long, value_enum, conflicts_with_all = ["only", "inline"],
+        help = "Prints the letters and the phonetic codes with the alphabet, the position, and the word index in the given format."
+    )]
+    format: Option<OutputFormat>,
+
+    #[arg(
+        long = "preserve-case", default_value_t = false,
+        help = "Spells the codes in the case of the letters (e.g., \"hotel\" for 'h'), which `decode` decodes into the original case."
+    )]
+    preserve_case: bool,
+
+    #[arg(help = "The words to encode using the specified phonetic code.
+Gives '-' read from stdin. No arguments also reads from stdin.")]
+    args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct DecodeOpts {
+    #[command(flatten)]
+    alphabet: AlphabetOpts,
+
+    #[command(flatten)]
+    inline: InlineOpts,
+
+    #[arg(
+        long, default_value_t = false,
+        help = "Decodes the phonetic codes ignoring the case, whitespaces, hyphens, and diacritics."
+    )]
+    tolerant: bool,
+
+    #[arg(
+        long, default_value_t = false,
+        help = "Reports the unrecognized phonetic codes to stderr, and exits with non-zero status."
+    )]
+    strict: bool,
+
+    #[arg(
+        long, value_enum,
+        help = "Prints the phonetic codes and the decoded letters with the alphabet, the position, and the word index in the given format."
+    )]
+    format: Option<OutputFormat>,
+
+    #[arg(help = "The phonetic codes to decode.
+Gives '-' read from stdin. No arguments also reads from stdin.")]
+    args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct ListOpts {
+    #[arg(long, default_value_t = false, help = "Prints the name, language, standard, and year of the phonetic codes.")]
+    long: bool,
+}
+
+#[derive(Args, Debug)]
+struct ShowOpts {
+    #[command(flatten)]
+    alphabet: AlphabetOpts,
+
+    #[arg(
+        long, value_enum,
+        help = "Prints the phonetic codes in the given format, the line format, TOML, and JSON are available for `--input`."
     )]
-    code: PhoneticCode,
+    format: Option<ExportFormat>,
 
-    #[arg(short, long, default_value_t = false, help = "Prints the available phonetic codes. ")]
+    #[arg(long = "only-code", default_value_t = false, conflicts_with = "format", help = "Prints the only letters of the phonetic codes.")]
+    only: bool,
+}
+
+#[derive(Args, Debug)]
+struct CheckOpts {
+    #[arg(value_name = "FILE", help = "The path to the custom phonetic code file to check.")]
+    file: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct ConvertOpts {
+    #[arg(short, long, value_enum, help = "The format to convert the file into.")]
+    format: ExportFormat,
+
+    #[arg(value_name = "FILE", help = "The path to the custom phonetic code file to convert.")]
+    file: PathBuf,
+}
+
+/// The deprecated flag forms before the commands, which are hidden from the help.
+#[derive(Args, Debug)]
+struct LegacyOpts {
+    #[command(flatten)]
+    alphabet: AlphabetOpts,
+
+    #[arg(short, long, hide = true, default_value_t = false, conflicts_with_all = ["print", "decode"])]
     list: bool,
 
-    #[arg(short, long, default_value_t = false, help = "Prints the phonetic codes for the given type.")]
+    #[arg(long, hide = true, default_value_t = false, requires = "list")]
+    long: bool,
+
+    #[arg(short, long, hide = true, default_value_t = false, conflicts_with = "decode")]
     print: bool,
 
-    #[arg(long = "only-code", default_value_t = false, help = "Prints the only phonetic code for the given words.")]
+    #[arg(long, hide = true, value_enum, requires = "print")]
+    format: Option<ExportFormat>,
+
+    #[arg(long = "only-code", hide = true, default_value_t = false, conflicts_with = "decode")]
     only: bool,
 
-    #[arg(short, long, default_value_t = false, help = "Decodes given phonetic codes into string.")]
+    #[arg(long = "preserve-case", hide = true, default_value_t = false, conflicts_with = "decode")]
+    preserve_case: bool,
+
+    #[arg(short, long, hide = true, default_value_t = false)]
     decode: bool,
 
-    #[arg(long, value_name = "FILE", help = "Specify the path to a custom phonetic code file.")]
-    input: Option<PathBuf>,
+    #[arg(long, hide = true, default_value_t = false, requires = "decode")]
+    tolerant: bool,
 
-    #[cfg(debug_assertions)]
-    #[arg(long, hide = true, default_value_t = false, help = "Generates completion files.")]
-    gencomp: bool,
+    #[arg(long, hide = true, default_value_t = false, requires = "decode")]
+    strict: bool,
 
-    #[arg(help = "The words to encode using the specified phonetic code.
-Gives '-' read from stdin. No arguments also reads from stdin.")]
+    #[arg(hide = true)]
     args: Vec<String>,
 }
 
+impl LegacyOpts {
+    /// Converts the flag forms into the command, with the deprecation warnings.
+    fn into_command(self) -> Command {
+        let LegacyOpts { alphabet, list, long, print, format, only, preserve_case, decode, tolerant, strict, args } = self;
+        if list {
+            deprecated("--list", "list");
+            Command::List(ListOpts { long })
+        } else if print {
+            deprecated("--print", "show");
+            Command::Show(ShowOpts { alphabet, format, only })
+        } else if decode {
+            deprecated("--decode", "decode");
+            Command::Decode(DecodeOpts { alphabet, inline: InlineOpts::default(), tolerant, strict, format: None, args })
+        } else {
+            Command::Encode(EncodeOpts { alphabet, inline: InlineOpts::default(), only, format: None, preserve_case, args })
+        }
+    }
+}
+
+fn deprecated(flag: &str, command: &str) {
+    eprintln!("warning: `{flag}` is deprecated, use `spellout {command}` instead.");
+}
+
+/// Returns the command line definition, the options of the flag forms are hidden from the top level help.
+fn cli_command() -> clap::Command {
+    ["code", "case", "no_separators", "input"].into_iter()
+        .fold(CliOpts::command(), |command, id| command.mut_arg(id, |arg| arg.hide(true)))
+}
+
+/// The phonetic code specified by `--code` option.
+#[derive(Clone, Debug)]
+enum CodeSpec {
+    /// Detects the phonetic code from the input (available only in decoding).
+    Auto,
+    /// The name of the alphabet in the registry.
+    Code(String),
+}
+
 fn print_all(codes: &Codes, only: bool) {
     for code in codes.entries() {
         if only {
-            println!("{}", code.letter());
+            println!("{}", code.letters());
         } else {
-            println!("{}    {}", code.letter(), code.code());
+            println!("{}    {}", code.letters(), code.code());
         }
     }
 }
 
-fn encode_string(codes: &Codes, only: bool, input: String) {
+fn print_encoded(letter: &str, code: Option<String>, only: bool) {
+    if !only {
+        print!("{}    ", letter.escape_debug());
+    }
+    println!("{}", code.unwrap_or_default());
+}
+
+/// Encodes the input, and gives each unit and its code to `emit`.
+fn encode_string(codes: &Codes, input: String, emit: &mut impl FnMut(String, Option<&Code>)) {
     for (letter, code) in codes.encode(input.as_ref()) {
-        if !only {
-            print!("{letter}    ");
-        }
-        if let Some(c) = code {
-            println!("{}", c.code());
-        } else {
-            println!();
-        }
+        emit(letter, code);
     }
 }
 
-fn handle_stdin(codes: &Codes, only: bool) {
-    for line in collect_inputs_from_stdin() {
-        encode_string(codes, only, line);
+fn handle_stdin(codes: &Codes, emit: &mut impl FnMut(String, Option<&Code>)) {
+    for (i, line) in collect_inputs_from_stdin().into_iter().enumerate() {
+        if i > 0 {
+            encode_string(codes, "\n".to_string(), emit);
+        }
+        encode_string(codes, line, emit);
     }
 }
 
-fn encode_words(codes: &Codes, only: bool, words: Vec<String>) {
+fn encode_words(codes: &Codes, words: Vec<String>, emit: &mut impl FnMut(String, Option<&Code>)) {
     if words.is_empty() {
-        handle_stdin(codes, only);
+        handle_stdin(codes, emit);
     } else {
         for (i, word) in words.into_iter().enumerate() {
             if i > 0 {
-                println!();
+                encode_string(codes, " ".to_string(), emit);
             }
             if word == "-" {
-                handle_stdin(codes, only);
+                handle_stdin(codes, emit);
             } else {
-                encode_string(codes, only, word);
+                encode_string(codes, word, emit);
             }
         }
     }
 }
 
-fn print_list() {
-    for pc in PhoneticCode::available_names() {
-        println!("{pc}");
+/// Returns the registry of the command, which contains the alphabet files in the search path over the shared registry.
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry::default().with_search_path(spellout::search_path()))
+}
+
+fn print_list(long: bool) {
+    let registry = registry();
+    if !long {
+        for name in registry.names() {
+            println!("{name}");
+        }
+        return;
+    }
+    let rows = registry.names()
+        .filter_map(|name| registry.metadata(name))
+        .map(|m| [
+            m.name().unwrap_or("-").to_string(),
+            m.language().unwrap_or("-").to_string(),
+            m.standard().unwrap_or("-").to_string(),
+            m.year().map(|y| y.to_string()).unwrap_or("-".to_string()),
+        ])
+        .collect::<Vec<_>>();
+    let header = ["NAME", "LANGUAGE", "STANDARD", "YEAR"].map(String::from);
+    let mut widths = [0; 4];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
     }
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row.iter().zip(widths)
+            .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - display_width(cell))))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Returns the width of the given string in the terminal, the east asian wide characters occupy two columns.
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c {
+            '\u{1100}'..='\u{115F}' | '\u{2E80}'..='\u{A4CF}' | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FF60}' | '\u{FFE0}'..='\u{FFE6}' => 2,
+            _ => 1,
+        })
+        .sum()
 }
 
 fn collect_inputs_from_stdin() -> Vec<String> {
@@ -103,7 +404,7 @@ fn collect_inputs_from_stdin() -> Vec<String> {
     inputs
 }
 
-fn decode_all(codes: &Codes, arg: Vec<String>) {
+fn collect_decode_inputs(arg: Vec<String>) -> Vec<String> {
     let mut inputs = Vec::new();
     if arg.is_empty() {
         inputs = collect_inputs_from_stdin();
@@ -116,47 +417,336 @@ fn decode_all(codes: &Codes, arg: Vec<String>) {
             }
         }
     }
-    println!("{}", codes.decode(inputs));
+    inputs
+}
+
+fn detect_codes(inputs: &[String], matching: Matching) -> Result<Codes, Error> {
+    let best = spellout::best_detection(registry().detect(&inputs.join("\n"), matching))?;
+    eprintln!("Detected phonetic code: {} (confidence: {:.2})", best.name(), best.confidence());
+    registry().codes(best.name()).cloned()
+}
+
+/// Splits the inputs into the tokens in the layout of the options, and decodes them.
+fn decode_report(codes: &Codes, options: DecodeOptions, opts: &DecodeOpts, inputs: Vec<String>) -> Result<DecodeReport, Error> {
+    let layout = opts.inline.options();
+    let tokens = inputs.into_iter()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            if line.trim().is_empty() {
+                vec![line] // blank line is a word break.
+            } else if let Some(layout) = &layout {
+                let tokens = codes.tokenize_inline(&line, layout, options.matching_mode());
+                // the lines in inline mode are the separated words.
+                if i == 0 { tokens } else { std::iter::once(String::new()).chain(tokens).collect() }
+            } else {
+                codes.tokenize(&line, options.matching_mode())
+            }
+        })
+        .collect();
+    codes.decode_report(tokens, options)
+}
+
+fn decode_all(codes: &Codes, options: DecodeOptions, opts: &DecodeOpts, inputs: Vec<String>) -> Result<(), Error> {
+    let report = decode_report(codes, options, opts, inputs)?;
+    let Some(format) = opts.format else {
+        match &opts.inline.unknown {
+            Some(marker) => println!("{}", report.tokens().iter()
+                .map(|token| token.letters.as_deref().unwrap_or(marker))
+                .collect::<String>()),
+            None => println!("{}", report.text()),
+        }
+        return Ok(());
+    };
+    let mut output = Output::new(format, codes.metadata().name());
+    for token in report.tokens() {
+        let blank = token.letters.as_deref().is_some_and(|letters| letters.trim().is_empty());
+        // the unknown token is a space in the decoded text.
+        let width = token.letters.as_deref().map_or(1, |letters| letters.chars().count());
+        output.push(token.letters.clone(), Some(token.text.clone()), width, blank);
+    }
+    output.finish()
 }
 
-fn parse_code(s: &str) -> Result<PhoneticCode, String> {
-    if let Ok(pc) = PhoneticCode::from_str(s, true) {
-        Ok(pc)
-    } else if spellout::is_available_name(s) {
-        Ok(PhoneticCode::Asset(s.to_string()))
+/// Checks the given code file, and prints the problems in the manner of the compilers.
+/// The bases of the file are resolved in the given registry.
+fn check_file(registry: &Registry, file: &Path) -> Result<(), Error> {
+    match registry.build_from_file_strict(file) {
+        Ok(codes) => {
+            println!("{}: ok ({} codes)", file.display(), codes.entries().count());
+            Ok(())
+        },
+        Err(Error::Diagnostics(diagnostics)) => {
+            let source = std::fs::read(file).map_err(Error::IO)?;
+            let lines = String::from_utf8_lossy(&source).lines().map(String::from).collect::<Vec<_>>();
+            for diagnostic in &diagnostics {
+                print_diagnostic(file, &lines, diagnostic);
+            }
+            Err(Error::Parse(format!("{}: {} problem(s) found", file.display(), diagnostics.len())))
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn print_diagnostic(file: &Path, lines: &[String], diagnostic: &Diagnostic) {
+    if diagnostic.text.is_empty() {
+        eprintln!("error: expected {}", diagnostic.expected);
     } else {
-        Err(format!("Invalid phonetic code: {s}"))
+        eprintln!("error: expected {}, found '{}'", diagnostic.expected, diagnostic.text);
+    }
+    let number = diagnostic.line.to_string();
+    let pad = " ".repeat(number.len());
+    eprintln!("{pad}--> {}:{}:{}", file.display(), diagnostic.line, diagnostic.column);
+    if let Some(line) = lines.get(diagnostic.line - 1) {
+        let indent = line.chars().take(diagnostic.column - 1)
+            .map(|c| if c == '\t' { "\t".to_string() } else { " ".repeat(display_width(&c.to_string())) })
+            .collect::<String>();
+        let marker = "^".repeat(display_width(&diagnostic.text).max(1));
+        eprintln!("{pad} |");
+        eprintln!("{number} | {line}");
+        eprintln!("{pad} | {indent}{marker}");
+    }
+    eprintln!();
+}
+
+fn parse_code(s: &str) -> Result<CodeSpec, String> {
+    if s.eq_ignore_ascii_case("auto") {
+        Ok(CodeSpec::Auto)
+    } else {
+        let registry = registry();
+        registry.resolve(s)
+            .map(|name| CodeSpec::Code(name.to_string()))
+            .ok_or_else(|| Error::UnknownPhoneticCode(s.to_string(), registry.suggest(s)).to_string())
     }
 }
 
 #[cfg(debug_assertions)]
 mod gencomp;
 
-fn perform(opts: CliOpts) -> Result<(), Error> {
-    let codes = if let Some(input) = opts.input {
-        CodesBuilder::build_from_file(input)?
+impl AlphabetOpts {
+    fn configure(&self, codes: Codes) -> Codes {
+        let codes = codes.with_case_policy(self.case);
+        if self.no_separators {
+            codes.with_separators(Separators::none())
+        } else {
+            codes
+        }
+    }
+
+    fn build(&self) -> Result<Codes, Error> {
+        self.build_in(registry())
+    }
+
+    /// Builds the alphabet of the options, the names and the bases of `--input` are resolved in the given registry.
+    fn build_in(&self, registry: &Registry) -> Result<Codes, Error> {
+        let codes = if let Some(input) = &self.input {
+            registry.build_from_file(input)?
+        } else if let CodeSpec::Code(name) = &self.code {
+            registry.codes(name)?.clone()
+        } else {
+            return Err(Error::UnknownPhoneticCode("auto (available only in decoding)".to_string(), Vec::new()));
+        };
+        Ok(self.configure(codes))
+    }
+}
+
+impl DecodeOpts {
+    fn options(&self) -> DecodeOptions {
+        let matching = if self.tolerant { Matching::Tolerant } else { Matching::Exact };
+        DecodeOptions::default().matching(matching).strict(self.strict)
+    }
+}
+
+impl EncodeOpts {
+    /// Spells the code of the letter, in the case of the letter with `--preserve-case` or `--only-code`,
+    /// so that `decode` restores the case from the only codes.
+    fn spell(&self, letter: &str, code: Option<&Code>) -> Option<String> {
+        code.map(|c| if self.preserve_case || self.only { c.spell(letter) } else { c.code().to_string() })
+    }
+}
+
+fn decode(opts: DecodeOpts) -> Result<(), Error> {
+    let options = opts.options();
+    let inputs = collect_decode_inputs(opts.args.clone());
+    let codes = if matches!(opts.alphabet.code, CodeSpec::Auto) && opts.alphabet.input.is_none() {
+        opts.alphabet.configure(detect_codes(&inputs, options.matching_mode())?)
     } else {
-        CodesBuilder::build(opts.code)
+        opts.alphabet.build()?
     };
-    #[cfg(debug_assertions)]
-    if cfg!(debug_assertions) && opts.gencomp {
-        gencomp::generate(std::path::Path::new("assets/completions"));
-    }
-    if opts.list {
-        print_list()
-    } else if opts.print {
-        print_all(&codes, opts.only);
-    } else if opts.decode {
-        decode_all(&codes, opts.args);
-    } else {
-        encode_words(&codes, opts.only, opts.args);
+    decode_all(&codes, options, &opts, inputs)
+}
+
+fn encode(mut opts: EncodeOpts) -> Result<(), Error> {
+    let codes = opts.alphabet.build()?;
+    let args = std::mem::take(&mut opts.args);
+    // the unknown marker is for the letters, and the whitespaces without the code (e.g., by `--no-separators`) stay blank.
+    let mark = |letter: &str, code: Option<String>| code.or_else(|| opts.inline.unknown.clone().filter(|_| !letter.trim().is_empty()));
+    if let Some(layout) = opts.inline.options() {
+        let layout = layout.preserve_case(opts.preserve_case);
+        let lines = if args.is_empty() {
+            collect_inputs_from_stdin()
+        } else {
+            vec![collect_decode_inputs(args).join(" ")]
+        };
+        for line in lines {
+            println!("{}", codes.spell_inline(&line, &layout));
+        }
+        return Ok(());
+    }
+    let Some(format) = opts.format else {
+        encode_words(&codes, args, &mut |letter, code| print_encoded(&letter, mark(&letter, opts.spell(&letter, code)), opts.only));
+        return Ok(());
+    };
+    let mut output = Output::new(format, codes.metadata().name());
+    encode_words(&codes, args, &mut |letter, code| {
+        let (width, blank) = (letter.chars().count(), letter.trim().is_empty());
+        let code = opts.spell(&letter, code);
+        output.push(Some(letter), code, width, blank);
+    });
+    output.finish()
+}
+
+fn show(opts: ShowOpts) -> Result<(), Error> {
+    let codes = opts.alphabet.build()?;
+    match opts.format {
+        Some(format) => codes.write_to(&mut std::io::stdout().lock(), format),
+        None => {
+            print_all(&codes, opts.only);
+            Ok(())
+        },
+    }
+}
+
+fn convert(opts: ConvertOpts) -> Result<(), Error> {
+    registry().build_from_file(&opts.file)?
+        .write_to(&mut std::io::stdout().lock(), opts.format)
+}
+
+fn perform(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Encode(opts) => encode(opts)?,
+        Command::Decode(opts) => decode(opts)?,
+        Command::List(opts) => print_list(opts.long),
+        Command::Show(opts) => show(opts)?,
+        Command::Check(opts) => check_file(registry(), &opts.file)?,
+        Command::Convert(opts) => convert(opts)?,
     }
     Ok(())
 }
 
+/// Moves the leading `--profile` option after the following command name, such as `spellout --profile radio encode`.
+/// Any argument before the command disables the commands, since the arguments of the flag forms conflict with them.
+fn normalize_args<I: IntoIterator<Item = std::ffi::OsString>>(args: I, command: &clap::Command) -> Vec<std::ffi::OsString> {
+    let mut args = args.into_iter().collect::<Vec<_>>();
+    let mut end = 1;
+    while let Some(arg) = args.get(end).and_then(|arg| arg.to_str()) {
+        if arg == "--profile" {
+            end += 2;
+        } else if arg.starts_with("--profile=") {
+            end += 1;
+        } else {
+            break;
+        }
+    }
+    let is_command = |arg: &std::ffi::OsString| arg.to_str()
+        .is_some_and(|name| command.get_subcommands().any(|sub| sub.get_name() == name));
+    if end > 1 && args.get(end).is_some_and(is_command) {
+        let name = args.remove(end);
+        args.insert(1, name);
+    }
+    args
+}
+
 fn main() {
-    let opts = CliOpts::parse();
-    if let Err(e) = perform(opts) {
-        eprintln!("Error: {e:?}");
+    let command = cli_command();
+    let matches = command.clone().get_matches_from(normalize_args(std::env::args_os(), &command));
+    let opts = match CliOpts::from_arg_matches(&matches) {
+        Ok(opts) => opts,
+        Err(e) => e.exit(),
+    };
+    #[cfg(debug_assertions)]
+    if cfg!(debug_assertions) && opts.gencomp {
+        gencomp::generate(Path::new("assets/completions"));
+    }
+    let mut command = opts.command.unwrap_or_else(|| opts.legacy.into_command());
+    let command_matches = matches.subcommand().map_or(&matches, |(_, matches)| matches);
+    let result = config::load(opts.profile)
+        .and_then(|settings| settings.apply(&mut command, command_matches))
+        .and_then(|_| perform(command));
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn try_parse(args: &[&str]) -> Result<CliOpts, clap::Error> {
+        let command = cli_command();
+        let matches = command.clone().try_get_matches_from(normalize_args(args.iter().map(Into::into), &command))?;
+        CliOpts::from_arg_matches(&matches)
+    }
+
+    fn parse(args: &[&str]) -> CliOpts {
+        try_parse(args).unwrap()
+    }
+
+    #[test]
+    fn test_file_bases_in_search_path() {
+        let registry = Registry::default().with_search_path(["testdata/layers/lower"]);
+        let Some(Command::Encode(opts)) = parse(&["spellout", "encode", "--input", "testdata/layers/smoothie.txt", "bs"]).command else {
+            panic!("the encode command should be parsed");
+        };
+        let smoothie = opts.alphabet.build_in(&registry).unwrap();
+        assert_eq!(smoothie.code('b').map(|c| c.code()), Some("Blueberry"));
+        assert_eq!(smoothie.code('s').map(|c| c.code()), Some("Strawberry"));
+        assert!(matches!(opts.alphabet.build_in(&Registry::default()), Err(Error::Parse(_))));
+
+        let file = Path::new("testdata/layers/smoothie.txt");
+        assert!(check_file(&registry, file).is_ok());
+        assert!(check_file(&Registry::default(), file).is_err());
+    }
+
+    #[test]
+    fn test_profile_before_command() {
+        for args in [&["spellout", "--profile", "radio", "encode", "ab c"][..], &["spellout", "--profile=radio", "encode", "ab c"]] {
+            let opts = parse(args);
+            assert_eq!(opts.profile.as_deref(), Some("radio"));
+            let Some(Command::Encode(encode)) = opts.command else {
+                panic!("{args:?} should be the encode command: {:?}", opts.command);
+            };
+            assert_eq!(encode.args, vec!["ab c"]);
+        }
+        let opts = parse(&["spellout", "--profile", "radio", "hello"]);
+        assert!(opts.command.is_none() && opts.profile.as_deref() == Some("radio"));
+        assert!(matches!(parse(&["spellout", "encode", "--profile", "radio", "x"]).command, Some(Command::Encode(_))));
+    }
+
+    #[test]
+    fn test_legacy_conflicts() {
+        for args in [&["spellout", "--strict", "Hotel"][..], &["spellout", "--tolerant", "hotel"], &["spellout", "--only-code", "--decode", "Hotel"]] {
+            assert!(try_parse(args).is_err(), "{args:?} should be rejected");
+        }
+        assert!(try_parse(&["spellout", "--decode", "--tolerant", "--strict", "hotel"]).is_ok());
+        assert!(try_parse(&["spellout", "--print", "--only-code"]).is_ok());
+    }
+
+    #[test]
+    fn test_only_code_round_trip() {
+        let Command::Encode(encode) = parse(&["spellout", "--only-code", "Hello World", "x-ray"]).legacy.into_command() else {
+            panic!("the flag form should be the encode command");
+        };
+        let codes = encode.alphabet.build().unwrap();
+        let mut lines = Vec::new();
+        encode_words(&codes, encode.args.clone(), &mut |letter, code| lines.push(encode.spell(&letter, code).unwrap_or_default()));
+        assert_eq!(lines[..2], ["Hotel", "echo"]);
+
+        let Command::Decode(decode) = parse(&["spellout", "--decode"]).legacy.into_command() else {
+            panic!("--decode should be the decode command");
+        };
+        let report = decode_report(&codes, decode.options(), &decode, lines).unwrap();
+        assert!(report.unknowns().is_empty());
+        assert_eq!(report.text(), "Hello World x-ray");
     }
 }
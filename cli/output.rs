@@ -0,0 +1,180 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use spellout::{quote_csv, Error};
+
+/// The machine readable formats of the results of `encode` and `decode` commands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// The JSON array of the records.
+    Json,
+    /// The JSON record per line.
+    Ndjson,
+    /// The comma separated values with the header row.
+    Csv,
+    /// The tab separated values with the header row.
+    Tsv,
+}
+
+/// A pair of the letter and its phonetic code in the result.
+#[derive(Serialize, Debug)]
+struct Record {
+    letter: Option<String>,
+    code: Option<String>,
+    alphabet: Option<String>,
+    /// The offset of the unit in characters, of the input text for encoding, and of the decoded text for decoding.
+    position: usize,
+    /// The index of the word which the unit belongs to, the words are delimited by the whitespaces.
+    word: usize,
+}
+
+const HEADER: [&str; 5] = ["letter", "code", "alphabet", "position", "word"];
+
+/// Writes the records into the writer (stdout by default) in the given format.
+/// The records of NDJSON, CSV, and TSV are written immediately, and those of JSON are written at [`Output::finish`].
+/// The first failure of writing is reported at [`Output::finish`].
+pub(crate) struct Output<W: Write = std::io::Stdout> {
+    writer: W,
+    format: OutputFormat,
+    alphabet: Option<String>,
+    records: Vec<Record>,
+    position: usize,
+    word: usize,
+    in_word: bool,
+    after_blank: bool,
+    error: Option<std::io::Error>,
+}
+
+impl Output {
+    pub(crate) fn new(format: OutputFormat, alphabet: Option<&str>) -> Self {
+        Output::with_writer(std::io::stdout(), format, alphabet)
+    }
+}
+
+impl<W: Write> Output<W> {
+    pub(crate) fn with_writer(writer: W, format: OutputFormat, alphabet: Option<&str>) -> Self {
+        let mut output = Output {
+            writer, format, alphabet: alphabet.map(String::from), records: Vec::new(),
+            position: 0, word: 0, in_word: false, after_blank: false, error: None,
+        };
+        match format {
+            OutputFormat::Csv => output.write_line(&HEADER.join(",")),
+            OutputFormat::Tsv => output.write_line(&HEADER.join("\t")),
+            _ => {},
+        }
+        output
+    }
+
+    /// Emits the letter and the code, which occupy `width` of the input.
+    /// The blank letter is a word break, and the next non-blank letter starts the next word.
+    pub(crate) fn push(&mut self, letter: Option<String>, code: Option<String>, width: usize, blank: bool) {
+        if blank {
+            self.after_blank = true;
+        } else {
+            if self.in_word && self.after_blank {
+                self.word += 1;
+            }
+            self.in_word = true;
+            self.after_blank = false;
+        }
+        let record = Record { letter, code, alphabet: self.alphabet.clone(), position: self.position, word: self.word };
+        self.position += width;
+        match self.format {
+            OutputFormat::Json => self.records.push(record),
+            OutputFormat::Ndjson => self.write_line(&serde_json::to_string(&record).unwrap_or_default()),
+            OutputFormat::Csv => self.write_line(&cells(&record).map(|cell| quote_csv(&cell)).join(",")),
+            OutputFormat::Tsv => self.write_line(&cells(&record).map(|cell| escape_tsv(&cell)).join("\t")),
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Result<(), Error> {
+        if self.format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&self.records)
+                .map_err(|e| Error::Parse(format!("failed to serialize into JSON: {e}")))?;
+            self.write_line(&json);
+        }
+        match self.error.take() {
+            Some(e) => Err(Error::IO(e)),
+            None => self.writer.flush().map_err(Error::IO),
+        }
+    }
+
+    /// Writes the line, the failure is kept for [`Output::finish`] and the later lines are discarded.
+    fn write_line(&mut self, line: &str) {
+        if self.error.is_none()
+            && let Err(e) = writeln!(self.writer, "{line}") {
+            self.error = Some(e);
+        }
+    }
+}
+
+/// Returns the cells of the record in the order of [`HEADER`], the missing values are empty.
+fn cells(record: &Record) -> [String; 5] {
+    [
+        record.letter.clone().unwrap_or_default(),
+        record.code.clone().unwrap_or_default(),
+        record.alphabet.clone().unwrap_or_default(),
+        record.position.to_string(),
+        record.word.to_string(),
+    ]
+}
+
+/// Escapes the backslash, the tab, and the line breaks of the cell of TSV.
+fn escape_tsv(cell: &str) -> String {
+    cell.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the records of "a,\"" followed by the unknown "€" in the given format.
+    fn write(format: OutputFormat) -> String {
+        let mut buffer = Vec::new();
+        let mut output = Output::with_writer(&mut buffer, format, Some("nato"));
+        output.push(Some("a".to_string()), Some("Alfa".to_string()), 1, false);
+        output.push(Some(",\"".to_string()), Some("Comma, \"Quote\"".to_string()), 2, false);
+        output.push(Some(" ".to_string()), Some("Space".to_string()), 1, true);
+        output.push(Some("€".to_string()), None, 1, false);
+        output.finish().unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(write(OutputFormat::Csv), "letter,code,alphabet,position,word
+a,Alfa,nato,0,0
+\",\"\"\",\"Comma, \"\"Quote\"\"\",nato,1,0
+ ,Space,nato,3,0
+€,,nato,4,1
+");
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert_eq!(write(OutputFormat::Tsv), "letter\tcode\talphabet\tposition\tword
+a\tAlfa\tnato\t0\t0
+,\"\tComma, \"Quote\"\tnato\t1\t0
+ \tSpace\tnato\t3\t0
+€\t\tnato\t4\t1
+");
+    }
+
+    #[test]
+    fn test_json() {
+        let ndjson = write(OutputFormat::Ndjson);
+        let lines = ndjson.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], r#"{"letter":",\"","code":"Comma, \"Quote\"","alphabet":"nato","position":1,"word":0}"#);
+        assert_eq!(lines[3], r#"{"letter":"€","code":null,"alphabet":"nato","position":4,"word":1}"#);
+
+        let json = serde_json::from_str::<serde_json::Value>(&write(OutputFormat::Json)).unwrap();
+        assert_eq!(json.as_array().map(Vec::len), Some(4));
+        assert_eq!(json[1]["code"], "Comma, \"Quote\"");
+        assert_eq!(json[3]["code"], serde_json::Value::Null);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(lines[3]).unwrap(), json[3]);
+    }
+}
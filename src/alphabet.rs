@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::{Code, Error, Metadata};
+
+/// Represents a phonetic alphabet, which provides the phonetic codes of its spelling units.
+///
+/// The builtin alphabets (see [`PhoneticCode`](crate::PhoneticCode)) implement this trait, and so can downstream crates,
+/// to ship their own alphabets as Rust types.
+/// Build the alphabet into [`Codes`](crate::Codes) with [`CodesBuilder::build_alphabet`](crate::CodesBuilder::build_alphabet),
+/// which validates that the letters and the spellings are unique in the alphabet.
+///
+/// ```rust
+/// use spellout::{Code, CodesBuilder, Error, PhoneticAlphabet};
+///
+/// struct Fruits;
+///
+/// impl PhoneticAlphabet for Fruits {
+///     fn name(&self) -> &str {
+///         "fruits"
+///     }
+///
+///     fn codes(&self) -> Result<Vec<Code>, Error> {
+///         Ok(vec![
+///             Code::try_new("A", "Apple")?,
+///             Code::try_new("B", "Banana")?,
+///             Code::try_new("C", "Cherry")?.try_with_aliases(&["Cherries"])?,
+///         ])
+///     }
+/// }
+///
+/// let codes = CodesBuilder::build_alphabet(&Fruits).unwrap();
+/// assert_eq!(codes.code('b').map(|c| c.code()), Some("Banana"));
+/// assert_eq!(codes.decode(vec!["Cherries".to_string(), "Apple".to_string()]), "CA");
+/// ```
+pub trait PhoneticAlphabet {
+    /// Returns the name of the alphabet, such as "nato".
+    fn name(&self) -> &str;
+
+    /// Returns the alternative names of the alphabet, which are also accepted in looking up the [`Registry`](crate::Registry).
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns the phonetic codes of the alphabet.
+    fn codes(&self) -> Result<Vec<Code>, Error>;
+
+    /// Returns the metadata of the alphabet, such as the language and the standard.
+    /// The name of the metadata is filled by [`PhoneticAlphabet::name`] in building the codes through the [`Registry`](crate::Registry).
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+}
+
+/// Validates the given phonetic codes as an alphabet.
+/// The alphabet must have at least one code, and the letters and the spellings (including the aliases) must be unique in the alphabet.
+pub(crate) fn validate(codes: &[Code]) -> Result<(), Error> {
+    validate_unique_letters(codes)?;
+    let mut spellings = HashMap::new();
+    for code in codes {
+        for spelling in code.spellings() {
+            if let Some(other) = spellings.insert(spelling, code) {
+                return Err(Error::InvalidCode(format!("{spelling}: spelling is shared by {} and {}", other.letters(), code.letters())));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates the given phonetic codes have at least one code, and the letters are unique.
+/// The spellings may be shared, such as the hiragana and the katakana of the Japanese alphabet.
+pub(crate) fn validate_unique_letters(codes: &[Code]) -> Result<(), Error> {
+    if codes.is_empty() {
+        return Err(Error::InvalidCode("the alphabet has no codes".to_string()));
+    }
+    let mut letters = HashMap::new();
+    for code in codes {
+        if let Some(other) = letters.insert(code.letters(), code) {
+            return Err(Error::InvalidCode(format!("{}: letters are defined twice ({}, {})", code.letters(), other.code(), code.code())));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the letters of a code, which must be non-empty and have no whitespaces or control characters.
+pub(crate) fn validate_letters(letters: &str) -> Result<(), Error> {
+    if letters.is_empty() {
+        Err(Error::InvalidCode("letters are empty".to_string()))
+    } else if letters.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        Err(Error::InvalidCode(format!("{}: letters contain whitespaces or control characters", letters.escape_debug())))
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates a spelling of a code, which must be non-blank, trimmed, and have no '|' (the separator of the aliases) or control characters.
+pub(crate) fn validate_spelling(spelling: &str) -> Result<(), Error> {
+    if spelling.trim().is_empty() {
+        Err(Error::InvalidCode("spelling is blank".to_string()))
+    } else if spelling.trim() != spelling {
+        Err(Error::InvalidCode(format!("'{spelling}': spelling has leading or trailing whitespaces")))
+    } else if spelling.chars().any(|c| c == '|' || c.is_control()) {
+        Err(Error::InvalidCode(format!("{}: spelling contains '|' or control characters", spelling.escape_debug())))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_code() {
+        assert!(Code::try_new("Sch", "Schule").is_ok());
+        assert!(Code::try_new("", "Empty").is_err());
+        assert!(Code::try_new("A B", "Space").is_err());
+        assert!(Code::try_new("A", " ").is_err());
+        assert!(Code::try_new("A", " Alpha").is_err());
+        assert!(Code::try_new("A", "Alfa|Alpha").is_err());
+        assert!(Code::try_new("A", "Alpha").and_then(|c| c.try_with_aliases(&["Alfa", ""])).is_err());
+    }
+
+    #[test]
+    fn test_validate_alphabet() {
+        let a = Code::try_new("A", "Alpha").unwrap();
+        let b = Code::try_new("B", "Bravo").unwrap();
+        assert!(validate(&[a.clone(), b.clone()]).is_ok());
+        assert!(validate(&[]).is_err());
+        assert!(validate(&[a.clone(), Code::try_new("A", "Able").unwrap()]).is_err());
+        assert!(validate(&[a, b.try_with_aliases(&["Alpha"]).unwrap()]).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use clap::ValueEnum;
+
+/// Case folding policies for matching the input letters against the spelling units of [`Codes`](crate::Codes).
+///
+/// Both the input and the spelling units are folded into upper case before comparison,
+/// so, for example, 'å' matches the 'Å' entry, and 'ς' (Greek final sigma) matches the 'Σ' entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, ValueEnum)]
+pub enum CasePolicy {
+    /// Full Unicode case mapping.
+    /// 'ß' is folded into 'ẞ' (capital sharp s), instead of "SS", to keep it a single letter.
+    #[default]
+    Unicode,
+    /// ASCII case mapping only, non-ASCII letters must match exactly.
+    Ascii,
+    /// Turkish and Azerbaijani case mapping, 'i' is folded into 'İ', and 'ı' is folded into 'I'.
+    /// The other letters follow the Unicode policy.
+    Turkish,
+}
+
+impl CasePolicy {
+    /// Folds the given string into upper case by this policy.
+    pub fn fold(&self, s: &str) -> String {
+        let mut result = String::with_capacity(s.len());
+        for c in s.chars() {
+            self.fold_char_into(c, &mut result);
+        }
+        result
+    }
+
+    /// Returns `true` if the given two strings are same after folding by this policy.
+    pub fn eq_folded(&self, a: &str, b: &str) -> bool {
+        a == b || self.fold(a) == self.fold(b)
+    }
+
+    fn fold_char_into(&self, c: char, dest: &mut String) {
+        match (self, c) {
+            (CasePolicy::Ascii, c) => dest.push(c.to_ascii_uppercase()),
+            (CasePolicy::Turkish, 'i') => dest.push('\u{0130}'),
+            (CasePolicy::Turkish, '\u{0131}') => dest.push('I'),
+            (_, '\u{00DF}') => dest.push('\u{1E9E}'),
+            (_, c) => dest.extend(c.to_uppercase()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode() {
+        let policy = CasePolicy::Unicode;
+        assert_eq!(policy.fold("åse"), "ÅSE");
+        assert_eq!(policy.fold("ß"), "\u{1E9E}");
+        assert_eq!(policy.fold("ς"), "Σ");
+        assert_eq!(policy.fold("ı"), "I");
+        assert_eq!(policy.fold("i"), "I");
+        assert!(policy.eq_folded("æ", "Æ"));
+    }
+
+    #[test]
+    fn test_ascii() {
+        let policy = CasePolicy::Ascii;
+        assert_eq!(policy.fold("abc"), "ABC");
+        assert!(!policy.eq_folded("ä", "Ä"));
+    }
+
+    #[test]
+    fn test_turkish() {
+        let policy = CasePolicy::Turkish;
+        assert_eq!(policy.fold("i"), "\u{0130}");
+        assert_eq!(policy.fold("ı"), "I");
+        assert_eq!(policy.fold("istanbul"), "\u{0130}STANBUL");
+        assert!(!policy.eq_folded("i", "I"));
+    }
+}
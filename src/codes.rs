@@ -9,10 +9,16 @@ mod netherlands;
 mod sweden;
 mod uk;
 mod japanese;
+mod variant;
 
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
-use crate::{Code, Codes, Error, PC};
-use include_dir::{include_dir, Dir};
+use std::path::{Component, Path, PathBuf};
+use crate::{CasePolicy, Code, Codes, Error, Metadata, PhoneticAlphabet, Registry};
+use crate::definition::{self, Format};
+use crate::diagnostic::{column_of, Diagnostic};
+use crate::metadata::{parse_header, HeaderError};
+use include_dir::{include_dir, Dir, File};
 
 pub(crate) use chp::Chp;
 pub(crate) use english::English;
@@ -25,74 +31,461 @@ pub(crate) use nato::Nato;
 pub(crate) use netherlands::Netherlands;
 pub(crate) use sweden::Sweden;
 pub(crate) use uk::Uk;
+pub(crate) use variant::Variant;
 
 static ASSETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/assets/codes");
 
-pub(crate) fn is_available_name(name: &str) -> bool {
-    ASSETS.get_file(format!("{}.txt", name.to_lowercase())).is_some()
+static USAAIRPOTS: [Code; 1] = [Code::fixed("D", "Dixie")];
+static INDONESIA: [Code; 1] = [Code::fixed("L", "London")];
+static PHILIPPINES: [Code; 1] = [Code::fixed("H", "Hawk")];
+
+/// Returns the builtin phonetic alphabets.
+pub(crate) fn builtins() -> Vec<Box<dyn PhoneticAlphabet + Send + Sync>> {
+    vec![
+        Box::new(Chp::new()),
+        Box::new(English::new()),
+        Box::new(Eu::new()),
+        Box::new(France::new()),
+        Box::new(International::new()),
+        Box::new(Italia::new()),
+        Box::new(Japanese::new()),
+        Box::new(Nato::new()),
+        Box::new(Netherlands::new()),
+        Box::new(Sweden::new()),
+        Box::new(Uk::new()),
+        // The following are custom phonetic codes that are based on the NATO alphabet, but with some substitutions for specific characters.
+        Box::new(Variant::new("usaairpots", &["usaairports"], Nato::new(), &USAAIRPOTS)
+            .with_metadata(Metadata::default().with_display_name("US Airports Phonetic Alphabet (NATO based)").with_language("en").with_region("US"))),
+        Box::new(Variant::new("indonesia", &[], Nato::new(), &INDONESIA)
+            .with_metadata(Metadata::default().with_display_name("Indonesian Phonetic Alphabet (NATO based)").with_language("en").with_region("ID"))),
+        Box::new(Variant::new("philippines", &[], Nato::new(), &PHILIPPINES)
+            .with_metadata(Metadata::default().with_display_name("Philippine Phonetic Alphabet (NATO based)").with_language("en").with_region("PH"))),
+    ]
+}
+
+/// Represents a phonetic alphabet defined in the embedded asset file.
+pub(crate) struct Asset {
+    name: String,
+    file: &'static File<'static>,
+    format: Format,
 }
 
-pub(crate) fn list_assets() -> Vec<String> {
+impl PhoneticAlphabet for Asset {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        build(self.file.contents(), self.format, false, &Context::asset(self.file.path()))
+            .map(|codes| codes.into_iter().collect())
+    }
+
+    fn metadata(&self) -> Metadata {
+        metadata_of(&self.name, self.file.contents(), self.format)
+    }
+}
+
+/// Represents a phonetic alphabet defined in the file of the search path (see [`Registry::with_search_path`](crate::Registry::with_search_path)).
+pub(crate) struct UserFile {
+    name: String,
+    path: PathBuf,
+    /// The directories to resolve the bases in, from the directory of the file to the lowest one of the search path.
+    layers: Vec<PathBuf>,
+}
+
+impl PhoneticAlphabet for UserFile {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        let content = std::fs::read(&self.path)
+            .map_err(Error::IO)?;
+        let context = Context::file(&self.path).with_layers(self.layers.clone());
+        build(&content, Format::detect(Some(&self.path), &content), false, &context)
+            .map(|codes| codes.into_iter().collect())
+    }
+
+    fn metadata(&self) -> Metadata {
+        match std::fs::read(&self.path) {
+            Ok(content) => metadata_of(&self.name, &content, Format::detect(Some(&self.path), &content)),
+            Err(e) => {
+                log::warn!("{}: failed to read the metadata: {e}", self.path.display());
+                Metadata::default()
+            },
+        }
+    }
+}
+
+/// The extensions of the alphabet files in the search path, in the line format, TOML, and JSON.
+const EXTENSIONS: [&str; 3] = ["txt", "toml", "json"];
+
+/// Returns the phonetic alphabets of the files in the first directory of the layers, named after the file stems, in order of the file names.
+/// The files in the line format (`.txt`), TOML (`.toml`), and JSON (`.json`) are the alphabets, and the subdirectories are not searched.
+/// The bases of the files are resolved in the layers (see [`Context::base`]).
+pub(crate) fn user_files(layers: &[PathBuf]) -> Result<Vec<UserFile>, Error> {
+    let Some(dir) = layers.first() else {
+        return Ok(Vec::new());
+    };
+    let mut files = std::fs::read_dir(dir)
+        .map_err(Error::IO)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()).is_some_and(|e| EXTENSIONS.contains(&e)))
+        .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(String::from)
+            .map(|name| UserFile { name, path, layers: layers.to_vec() }))
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Returns the metadata in the headers of the line format, or in the `[metadata]` table of the structured formats.
+fn metadata_of(name: &str, content: &[u8], format: Format) -> Metadata {
+    if format != Format::Lines {
+        return definition::metadata(content, format);
+    }
+    let mut metadata = Metadata::default();
+    for (key, value) in String::from_utf8_lossy(content).lines().filter_map(parse_header) {
+        if let Err(e) = metadata.set(key, value) {
+            log::warn!("{name}: invalid metadata header '{}', expected {}", key.trim(), e.expected());
+        }
+    }
+    metadata
+}
+
+/// Returns the phonetic alphabets of the embedded asset files in the line format (`.txt`), TOML (`.toml`), or JSON (`.json`).
+pub(crate) fn assets() -> Vec<Asset> {
     ASSETS.files()
-        .filter_map(|entry| entry.path().to_str().map(|s| s.to_string()))
-        .filter_map(|s| s.strip_suffix(".txt").map(|name| name.to_string()))
+        .filter_map(|file| {
+            let path = file.path();
+            let name = path.file_stem()?.to_str()?;
+            match path.extension()?.to_str()? {
+                "txt" | "toml" | "json" => Some(Asset { name: name.to_string(), file, format: Format::detect(Some(path), file.contents()) }),
+                _ => None,
+            }
+        })
         .collect()
 }
 
-pub(crate) fn build_from_asset(name: &str) -> Result<Codes, Error> {
-    let asset_name = format!("{}.txt", name.to_lowercase());
-    if let Some(file) = ASSETS.get_file(asset_name) {
-        build_from_reader(file.contents())
-    } else {
-        Err(Error::Asset(name.to_string()))
+/// Builds the codes from the content of the definition file in the given format.
+/// In the strict mode, the problems in the content result in [`Error::Diagnostics`].
+pub(crate) fn build(content: &[u8], format: Format, strict: bool, context: &Context) -> Result<Codes, Error> {
+    match format {
+        Format::Lines => build_lines(content, strict, context),
+        _ => definition::build(content, format, strict, context),
     }
 }
 
+/// Builds the codes from the definition file, detecting the format by the extension or the content.
+/// The bases of the definition are resolved in the given registry.
+pub(crate) fn build_from_path(path: &Path, strict: bool, registry: &Registry) -> Result<Codes, Error> {
+    let content = std::fs::read(path)
+        .map_err(Error::IO)?;
+    let context = Context { registry: Some(registry), ..Context::file(path) };
+    build(&content, Format::detect(Some(path), &content), strict, &context)
+}
+
+/// Represents the location of the definition being parsed, to resolve the included files and the bases, and to detect the cyclic inclusions.
+#[derive(Clone, Default)]
+pub(crate) struct Context<'a> {
+    /// The path of the definition, which is `None` for the content read from a reader.
+    path: Option<PathBuf>,
+    /// `true` if the path is in the embedded assets.
+    asset: bool,
+    /// The paths of the definitions including (or inheriting) this definition, from the outermost.
+    including: Vec<PathBuf>,
+    /// The directories to resolve the bases in, which are empty for the definitions out of the search path.
+    layers: Vec<PathBuf>,
+    /// The registry to resolve the bases in after the layers, which is the shared registry if `None`.
+    registry: Option<&'a Registry>,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn file(path: &Path) -> Context<'a> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        Context { path: Some(path), ..Default::default() }
+    }
+
+    fn asset(path: &Path) -> Context<'a> {
+        Context { path: Some(path.to_path_buf()), asset: true, ..Default::default() }
+    }
+
+    fn with_layers(self, layers: Vec<PathBuf>) -> Context<'a> {
+        Context { layers, ..self }
+    }
+
+    /// Builds the codes of the base of the given name.
+    /// The file of the name in the layers, except this definition, takes precedence over the alphabet of the registry,
+    /// and is built in the layers from its directory, in the same manner as the included files.
+    pub(crate) fn base(&self, name: &str, strict: bool) -> Result<Codes, Error> {
+        for (i, dir) in self.layers.iter().enumerate() {
+            let found = EXTENSIONS.iter()
+                .filter_map(|extension| dir.join(format!("{name}.{extension}")).canonicalize().ok())
+                .find(|path| path.is_file() && Some(path) != self.path.as_ref());
+            if let Some(resolved) = found {
+                let content = std::fs::read(&resolved).map_err(Error::IO)?;
+                return self.load(&content, resolved, self.layers[i..].to_vec(), strict);
+            }
+        }
+        match self.registry {
+            Some(registry) => registry.codes(name).cloned(),
+            None => crate::registry().codes(name).cloned(),
+        }
+    }
+
+    /// Builds the codes of the given file relative to the directory of this definition.
+    pub(crate) fn include(&self, target: &str, strict: bool) -> Result<Codes, Error> {
+        let Some(path) = &self.path else {
+            return Err(Error::Parse(format!("{target}: include is available only in the definition files")));
+        };
+        let joined = normalize(&path.parent().unwrap_or(Path::new("")).join(target));
+        let (content, resolved) = if self.asset {
+            let file = ASSETS.get_file(&joined)
+                .ok_or_else(|| Error::FileNotFound(joined.display().to_string(), None))?;
+            (file.contents().to_vec(), joined)
+        } else {
+            let resolved = joined.canonicalize()
+                .map_err(|e| Error::FileNotFound(joined.display().to_string(), Some(e)))?;
+            (std::fs::read(&resolved).map_err(Error::IO)?, resolved)
+        };
+        self.load(&content, resolved, self.layers.clone(), strict)
+    }
+
+    /// Builds the codes of the resolved file, which is included or inherited by this definition, and detects the cyclic inclusion.
+    fn load(&self, content: &[u8], resolved: PathBuf, layers: Vec<PathBuf>, strict: bool) -> Result<Codes, Error> {
+        let mut including = self.including.clone();
+        including.extend(self.path.clone());
+        if let Some(i) = including.iter().position(|p| p == &resolved) {
+            let chain = including[i..].iter().chain([&resolved]).map(|p| p.display().to_string()).collect();
+            return Err(Error::Cycle(chain));
+        }
+        let format = Format::detect(Some(&resolved), content);
+        let context = Context { path: Some(resolved), asset: self.asset, including, layers, registry: self.registry };
+        build(content, format, strict, &context)
+    }
+}
+
+/// Removes the `.` and `..` components of the path lexically, for the paths in the embedded assets.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                result.pop();
+            },
+            component => result.push(component),
+        }
+    }
+    result
+}
+
+/// Converts the error in resolving the base phonetic code.
+/// The cyclic inheritance is reported as it is, and the other errors are reported as [`Error::Parse`].
+pub(crate) fn base_error(e: Error) -> Error {
+    match e {
+        Error::Cycle(_) => e,
+        e => Error::Parse(format!("specified base code is not available: {e}")),
+    }
+}
+
+/// Removes the codes of the given letters and returns `true` if any code is removed.
+/// The letters match through the case policy of the alphabets built from the definitions, so "a" removes the code of "A".
+pub(crate) fn remove_letters(codes: &mut Vec<Code>, letters: &str) -> bool {
+    let count = codes.len();
+    codes.retain(|code| !CasePolicy::default().eq_folded(code.letters(), letters));
+    codes.len() < count
+}
+
+/// The delimiters between the letters and the code in a line of the code file.
+const DELIMITERS: [char; 6] = [',', ';', ':', '=', ' ', '\t'];
+
+/// The result of parsing a code file, with the problems found in the lines.
+struct Parsed {
+    codes: Vec<Code>,
+    metadata: Metadata,
+    diagnostics: Vec<Diagnostic>,
+    /// The warnings of the lines kept in the lenient parsing, such as the letters defined twice, whose later definition wins.
+    warnings: Vec<String>,
+    /// The error of the unavailable base code or the included file, which fails the lenient parsing as well.
+    fatal: Option<Error>,
+}
+
+/// Builds the codes from the code file, skipping the invalid lines with warnings.
+#[cfg(test)]
 pub(crate) fn build_from_reader(reader: impl std::io::Read) -> Result<Codes, Error> {
+    build_lines(reader, false, &Context::default())
+}
+
+/// Builds the codes from the code file.
+/// In the strict mode, this function returns [`Error::Diagnostics`] if any problem is found,
+/// otherwise, the invalid lines are skipped with warnings.
+fn build_lines(reader: impl std::io::Read, strict: bool, context: &Context) -> Result<Codes, Error> {
+    let parsed = parse(reader, strict, context);
+    if strict && !parsed.diagnostics.is_empty() {
+        return Err(Error::Diagnostics(parsed.diagnostics));
+    } else if let Some(e) = parsed.fatal {
+        return Err(e);
+    }
+    for diagnostic in &parsed.diagnostics {
+        log::warn!("Skipped the invalid line {diagnostic}");
+    }
+    for warning in &parsed.warnings {
+        log::warn!("{warning}");
+    }
+    Ok(parsed.codes.into_iter().collect::<Codes>().with_metadata(parsed.metadata))
+}
+
+fn parse(reader: impl std::io::Read, strict: bool, context: &Context) -> Parsed {
     let reader = BufReader::new(reader);
-    let mut codes = Vec::new();
-    for line in reader.lines() {
-        let line = match line {
+    let mut parsed = Parsed { codes: Vec::new(), metadata: Metadata::default(), diagnostics: Vec::new(), warnings: Vec::new(), fatal: None };
+    let mut defined = HashMap::new(); // letters -> the line number defining them.
+    for (i, line) in reader.split(b'\n').enumerate() {
+        let number = i + 1;
+        let line = match line.map_err(|e| e.to_string()).and_then(|bytes| String::from_utf8(bytes).map_err(|e| e.to_string())) {
             Err(e) => {
                 log::warn!("Failed to read a line from asset: {e}");
+                parsed.diagnostics.push(Diagnostic::new(number, 1, "", "a readable line in UTF-8"));
                 continue; // Skip lines that can't be read
             },
             Ok(l) => l,
         };
-        let line = trim_and_strip_comments(&line);
-        if line.is_empty() || line.starts_with('#') {
+        if let Some((key, value)) = parse_header(&line) {
+            if let Err(e) = parsed.metadata.set(key, value) {
+                let piece = match e {
+                    HeaderError::UnknownKey => key.trim(),
+                    HeaderError::InvalidValue(_) => value.trim(),
+                };
+                parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, piece), piece, e.expected()));
+            }
+            continue;
+        }
+        let content = trim_and_strip_comments(&line);
+        if content.is_empty() || content.starts_with('#') {
             continue;
-        } else if let Some(base_code) = line.strip_prefix("base:") {
-            let base_code = base_code.trim();
-            if let Ok(base) = base_code.parse::<Codes>() {
-                base.entries().for_each(|entry| codes.push(entry.clone()));
-            } else {
-                return Err(Error::Parse(format!("{base_code}: specified base code is not available")));
+        } else if let Some(bases) = content.strip_prefix("base:") {
+            // the multiple bases are layered in order, the later ones override the earlier ones.
+            for base_code in bases.split([',', ' ', '\t']).filter(|name| !name.is_empty()) {
+                match context.base(base_code, strict) {
+                    Ok(base) => parsed.codes.extend(base),
+                    Err(e) => {
+                        let expected = match &e {
+                            Error::UnknownPhoneticCode(_, suggestions) if !suggestions.is_empty() => {
+                                let suggestions = suggestions.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>().join(", ");
+                                format!("an available phonetic code name, such as {suggestions}")
+                            },
+                            Error::Cycle(_) => format!("a base without the cyclic inheritance ({e})"),
+                            Error::Diagnostics(diagnostics) => format!("a valid base, but {} problem(s) found in it", diagnostics.len()),
+                            _ => "an available phonetic code name".to_string(),
+                        };
+                        parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, base_code), base_code, expected));
+                        parsed.fatal.get_or_insert(base_error(e));
+                    },
+                }
             }
-        } else {
-            let parts: Vec<&str> = line.splitn(2, [',', ';', ':', '=', ' ', '\t']).collect();
-            if parts.len() == 2 {
-                let letter = if parts[0].len() == 1 {
-                    parts[0].chars().next().unwrap_or_default()
-                } else if parts[0].starts_with("\\u{") && parts[0].ends_with('}') {
-                    parse_unicode_letter(parts[0])
-                } else {
-                    continue; // Skip invalid lines
+        } else if let Some(target) = content.strip_prefix("include:") {
+            let target = target.trim();
+            match context.include(target, strict) {
+                Ok(included) => parsed.codes.extend(included),
+                Err(e) => {
+                    let expected = match &e {
+                        Error::Diagnostics(diagnostics) => format!("a valid file to include, but {} problem(s) found in it", diagnostics.len()),
+                        e => format!("a file to include ({e})"),
+                    };
+                    parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, target), target, expected));
+                    parsed.fatal.get_or_insert(e);
+                },
+            }
+        } else if let Some(letters) = content.strip_prefix("remove:") {
+            for letter in letters.split([',', ' ', '\t']).filter(|letter| !letter.is_empty()) {
+                let removing = match parse_letters(letter) {
+                    Ok(removing) => removing,
+                    Err(part) => {
+                        parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, part), part, UNICODE_ESCAPE));
+                        continue;
+                    },
                 };
-                let code = parts[1].trim().to_string();
-                codes.push(Code::new(letter, code));
+                defined.retain(|defined: &String, _| !CasePolicy::default().eq_folded(defined, &removing));
+                if !remove_letters(&mut parsed.codes, &removing) {
+                    parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, letter), letter, "the letters defined before the directive"));
+                }
+            }
+        } else {
+            match parse_line(&line, content) {
+                Ok((code, empty_alias)) => {
+                    if let Some(piece) = empty_alias {
+                        parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, piece), "", "a spelling after '|'"));
+                    }
+                    if let Some(previous) = defined.insert(code.letters().to_string(), number) {
+                        if strict {
+                            let expected = format!("unique letters, which are already defined in line {previous}");
+                            parsed.diagnostics.push(Diagnostic::new(number, column_of(&line, content), code.letters(), expected));
+                        } else {
+                            let letters = code.letters();
+                            parsed.warnings.push(format!("{number}:{}: duplicate definition of '{letters}' (first defined at line {previous})", column_of(&line, content)));
+                        }
+                    }
+                    parsed.codes.push(code);
+                },
+                Err(diagnostic) => parsed.diagnostics.push(Diagnostic { line: number, ..diagnostic }),
             }
         }
     }
-    Ok(Codes::new_of(PC::Null, codes))
+    parsed
+}
+
+/// Parses the content of a line (without comments) into the code.
+/// The empty alias, such as the trailing '|', is returned with the code, since the line is still valid without it.
+fn parse_line<'a>(line: &str, content: &'a str) -> Result<(Code, Option<&'a str>), Diagnostic> {
+    let Some((letters, spellings)) = content.split_once(DELIMITERS) else {
+        let expected = "a delimiter (',', ';', ':', '=', space, or tab) and the code after the letters";
+        return Err(Diagnostic::new(0, column_of(line, content) + content.chars().count(), "", expected));
+    };
+    let letters = parse_letters(letters)
+        .map_err(|part| Diagnostic::new(0, column_of(line, part), part, UNICODE_ESCAPE))?;
+    if letters.is_empty() {
+        return Err(Diagnostic::new(0, column_of(line, content), "", "the letters before the delimiter"));
+    }
+    let mut spellings = spellings.split('|').map(str::trim);
+    let code = spellings.next().unwrap_or_default();
+    if code.is_empty() {
+        return Err(Diagnostic::new(0, column_of(line, content) + content.chars().count(), "", "a code after the delimiter"));
+    }
+    let (aliases, empty): (Vec<_>, Vec<_>) = spellings.partition(|s| !s.is_empty());
+    Ok((Code::new(letters, code).with_aliases(&aliases), empty.first().copied()))
 }
 
-fn parse_unicode_letter(s: &str) -> char {
+/// The description of the valid unicode escape for the diagnostics.
+const UNICODE_ESCAPE: &str = "a unicode escape in hex digits, such as '\\u{00C4}'";
+
+/// Parses the letters, which may be the escaped letters, such as `\u{00C4}`.
+fn parse_letters(letters: &str) -> Result<String, &str> {
+    if letters.starts_with("\\u{") && letters.ends_with('}') {
+        parse_unicode_letters(letters)
+    } else {
+        Ok(letters.to_string())
+    }
+}
+
+/// Parses an escaped letter, such as `\u{00C4}`, and returns `None` if the hex digits are not a valid code point.
+fn parse_unicode_letter(s: &str) -> Option<char> {
     let hex = &s[3..s.len() - 1]; // Extract the hex part from \u{...}
     u32::from_str_radix(hex, 16)
         .ok()
         .and_then(std::char::from_u32)
-        .unwrap_or_default() // Return default char if parsing fails
+}
+
+/// Parses a sequence of escaped letters, such as `\u{0053}\u{0063}\u{0068}`, into a string.
+/// This function returns the invalid escape in the given string as the error.
+fn parse_unicode_letters(s: &str) -> Result<String, &str> {
+    let mut letters = String::new();
+    for part in s.split_inclusive('}') {
+        if part.starts_with("\\u{") && part.ends_with('}') {
+            letters.push(parse_unicode_letter(part).ok_or(part)?);
+        } else {
+            letters.push_str(part);
+        }
+    }
+    Ok(letters)
 }
 
 fn trim_and_strip_comments(line: &str) -> &str {
@@ -115,10 +508,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_list_assets() {
-        let assets = list_assets();
-        assert!(assets.contains(&"denmark".to_string()));
-        assert!(assets.contains(&"switzerland".to_string()));
+    fn test_assets() {
+        let assets = assets();
+        assert!(assets.iter().any(|asset| asset.name() == "denmark"));
+        assert!(assets.iter().any(|asset| asset.name() == "switzerland"));
         assert!(assets.len() >= 6);
     }
 
@@ -134,9 +527,100 @@ mod tests {
 
     #[test]
     fn test_parse_unicode_letter() {
-        assert_eq!(parse_unicode_letter("\\u{41}"), 'A');
-        assert_eq!(parse_unicode_letter("\\u{1F600}"), '😀');
-        assert_eq!(parse_unicode_letter("\\u{00C4}"), 'Ä');
-        assert_eq!(parse_unicode_letter("\\u{ZZZZ}"), '\0'); // Invalid hex should return default char
+        assert_eq!(parse_unicode_letter("\\u{41}"), Some('A'));
+        assert_eq!(parse_unicode_letter("\\u{1F600}"), Some('😀'));
+        assert_eq!(parse_unicode_letter("\\u{00C4}"), Some('Ä'));
+        assert_eq!(parse_unicode_letter("\\u{ZZZZ}"), None); // Invalid hex is not a letter
+        assert_eq!(parse_unicode_letters("\\u{49}\\u{ZZ}"), Err("\\u{ZZ}"));
+    }
+
+    #[test]
+    fn test_build_from_reader_with_digraphs() {
+        let codes = build_from_reader("A, Anton\nCh, Charlotte\nSch, Schule\n\\u{49}\\u{4A}, IJmuiden".as_bytes()).unwrap();
+        assert_eq!(codes.lookup("Ch").map(|c| c.code()), Some("Charlotte"));
+        assert_eq!(codes.lookup("Sch").map(|c| c.code()), Some("Schule"));
+        assert_eq!(codes.lookup("IJ").map(|c| c.code()), Some("IJmuiden"));
+        assert_eq!(codes.entries().count(), 4);
+    }
+
+    #[test]
+    fn test_build_from_reader_with_metadata() {
+        let codes = build_from_reader("#@ language: en\n#@ year: 1943\n# comment\nA, Able".as_bytes()).unwrap();
+        assert_eq!(codes.metadata().language(), Some("en"));
+        assert_eq!(codes.metadata().year(), Some(1943));
+        assert_eq!(codes.entries().count(), 1);
+        let asset = assets().into_iter().find(|asset| asset.name() == "switzerland").unwrap();
+        assert_eq!(asset.metadata().region(), Some("CH"));
+    }
+
+    #[test]
+    fn test_build_from_reader_with_aliases() {
+        let codes = build_from_reader("A, Alfa | Alpha\nJ, Juliett|Juliet|\nK, Kilo".as_bytes()).unwrap();
+        assert_eq!(codes.lookup("A").map(|c| c.code()), Some("Alfa"));
+        assert_eq!(codes.lookup("A").map(|c| c.aliases().collect::<Vec<_>>()), Some(vec!["Alpha"]));
+        assert_eq!(codes.lookup("J").map(|c| c.aliases().collect::<Vec<_>>()), Some(vec!["Juliet"]));
+        assert_eq!(codes.lookup("K").map(|c| c.aliases().count()), Some(0));
+    }
+
+    #[test]
+    fn test_build_from_reader_strict() {
+        let text = "#@ year: soon\n#@ author: someone\nA, Alfa\nBravo\n\\u{ZZ}, Zulu\nC,\nD, Delta|\nA, Alpha\nbase: netherland\n";
+        let Err(Error::Diagnostics(diagnostics)) = build_lines(text.as_bytes(), true, &Context::default()) else {
+            panic!("the problems should be reported");
+        };
+        let positions = diagnostics.iter().map(|d| (d.line, d.column, d.text.as_str())).collect::<Vec<_>>();
+        assert_eq!(positions, vec![
+            (1, 10, "soon"), (2, 4, "author"), (4, 6, ""), (5, 1, "\\u{ZZ}"), (6, 3, ""), (7, 10, ""), (8, 1, "A"), (9, 7, "netherland"),
+        ]);
+        assert!(diagnostics[7].expected.contains("'netherlands'"));
+        assert!(build_from_reader(text.as_bytes()).is_err()); // the unavailable base fails the lenient parsing as well.
+
+        let codes = build_from_reader(&text.as_bytes()[..text.find("base:").unwrap()]).unwrap();
+        assert_eq!(codes.lookup("A").map(|c| c.code()), Some("Alpha"));
+        assert_eq!(codes.entries().count(), 2); // A and D
+        let parsed = parse(&text.as_bytes()[..text.find("base:").unwrap()], false, &Context::default());
+        assert_eq!(parsed.warnings, vec!["8:1: duplicate definition of 'A' (first defined at line 3)"]);
+        assert!(parsed.diagnostics.iter().all(|d| d.line != 8));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_assets_have_no_diagnostics() {
+        for asset in assets() {
+            if let Err(e) = build(asset.file.contents(), asset.format, true, &Context::asset(asset.file.path())) {
+                panic!("{}: {e}", asset.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_from_reader_with_directives() {
+        let codes = build_from_reader("base: eu, nato\nremove: Ä \\u{00D6} Ü\nA, Apple".as_bytes()).unwrap();
+        let nato = "nato".parse::<Codes>().unwrap();
+        assert_eq!(codes.code('b').map(|c| c.code()), nato.code('b').map(|c| c.code()));
+        assert_eq!(codes.code('a').map(|c| c.code()), Some("Apple"));
+        assert!(codes.code('Ä').is_none() && codes.code('Ö').is_none() && codes.code('Ü').is_none());
+        let codes = build_lines("base: nato\nremove: a, b".as_bytes(), true, &Context::default()).unwrap();
+        assert!(codes.code('a').is_none() && codes.code('B').is_none());
+
+        let Err(Error::Diagnostics(diagnostics)) = build_lines("remove: Ä\ninclude: other.txt".as_bytes(), true, &Context::default()) else {
+            panic!("the problems should be reported");
+        };
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column)).collect::<Vec<_>>(), vec![(1, 9), (2, 10)]);
+    }
+
+    #[test]
+    fn test_build_from_path_with_includes() {
+        let codes = build_from_path(Path::new("testdata/include/berries.txt"), true, crate::registry()).unwrap();
+        assert_eq!(codes.code('a').map(|c| c.code()), Some("Apple"));
+        assert_eq!(codes.code('b').map(|c| c.code()), Some("Berry"));
+        assert!(codes.lookup("Ch").is_none());
+        assert_eq!(codes.metadata().display_name(), None); // the metadata is not included.
+
+        let error = build_from_path(Path::new("testdata/include/ping.txt"), false, crate::registry()).unwrap_err();
+        let Error::Cycle(chain) = error else {
+            panic!("the cyclic inclusion should be reported: {error}");
+        };
+        assert!(chain.first().unwrap().ends_with("ping.txt") && chain.last().unwrap().ends_with("ping.txt"));
+        assert_eq!(chain.len(), 3);
+    }
+}
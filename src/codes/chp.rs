@@ -1,50 +1,55 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Chp {
-    codes: Vec<Code>,
-}
+pub struct Chp;
 
 impl Chp {
     pub fn new() -> Self {
-        Chp {
-            codes: phonetic_codes(),
-        }
+        Chp
     }
 }
 
 impl PhoneticAlphabet for Chp {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "chp"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Adam"),
-        Code::new('B', "Baker"),
-        Code::new('C', "Charlie"),
-        Code::new('D', "David"),
-        Code::new('E', "Edward"),
-        Code::new('F', "Frank"),
-        Code::new('G', "George"),
-        Code::new('H', "Henry"),
-        Code::new('I', "Ida"),
-        Code::new('J', "John"),
-        Code::new('K', "King"),
-        Code::new('L', "Lewis"),
-        Code::new('M', "Mary"),
-        Code::new('N', "Nancy"),
-        Code::new('O', "Otto"),
-        Code::new('P', "Peter"),
-        Code::new('Q', "Queen"),
-        Code::new('R', "Robert"),
-        Code::new('S', "Sugar"),
-        Code::new('T', "Thomas"),
-        Code::new('U', "Uniform"),
-        Code::new('V', "Victor"),
-        Code::new('W', "William"),
-        Code::new('X', "X-ray"),
-        Code::new('Y', "Young"),
-        Code::new('Z', "Zebra"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("California Highway Patrol Phonetic Alphabet")
+            .with_language("en")
+            .with_region("US")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Adam"),
+    Code::fixed("B", "Baker"),
+    Code::fixed("C", "Charlie"),
+    Code::fixed("D", "David"),
+    Code::fixed("E", "Edward"),
+    Code::fixed("F", "Frank"),
+    Code::fixed("G", "George"),
+    Code::fixed("H", "Henry"),
+    Code::fixed("I", "Ida"),
+    Code::fixed("J", "John"),
+    Code::fixed("K", "King"),
+    Code::fixed("L", "Lewis"),
+    Code::fixed("M", "Mary"),
+    Code::fixed("N", "Nancy"),
+    Code::fixed("O", "Otto"),
+    Code::fixed("P", "Peter"),
+    Code::fixed("Q", "Queen"),
+    Code::fixed("R", "Robert"),
+    Code::fixed("S", "Sugar"),
+    Code::fixed("T", "Thomas"),
+    Code::fixed("U", "Uniform"),
+    Code::fixed("V", "Victor"),
+    Code::fixed("W", "William"),
+    Code::fixed("X", "X-ray"),
+    Code::fixed("Y", "Young"),
+    Code::fixed("Z", "Zebra"),
+];
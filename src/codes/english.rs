@@ -1,50 +1,54 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct English {
-    codes: Vec<Code>,
-}
+pub struct English;
 
 impl English {
     pub fn new() -> Self {
-        English {
-            codes: phonetic_codes(),
-        }
+        English
     }
 }
 
 impl PhoneticAlphabet for English {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "english"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Alfred"),
-        Code::new('B', "Benjamin"),
-        Code::new('C', "Charles"),
-        Code::new('D', "David"),
-        Code::new('E', "Edward"),
-        Code::new('F', "Frederick"),
-        Code::new('G', "George"),
-        Code::new('H', "Harry"),
-        Code::new('I', "Isaac"),
-        Code::new('J', "Jack"),
-        Code::new('K', "King"),
-        Code::new('L', "London"),
-        Code::new('M', "Mary"),
-        Code::new('N', "Nellie"),
-        Code::new('O', "Oliver"),
-        Code::new('P', "Peter"),
-        Code::new('Q', "Queen"),
-        Code::new('R', "Robert"),
-        Code::new('S', "Samuel"),
-        Code::new('T', "Tommy"),
-        Code::new('U', "Uncle"),
-        Code::new('V', "Victor"),
-        Code::new('W', "William"),
-        Code::new('X', "X-ray"),
-        Code::new('Y', "Yellow"),
-        Code::new('Z', "Zebra"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("English Spelling Alphabet (Given Names)")
+            .with_language("en")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Alfred"),
+    Code::fixed("B", "Benjamin"),
+    Code::fixed("C", "Charles"),
+    Code::fixed("D", "David"),
+    Code::fixed("E", "Edward"),
+    Code::fixed("F", "Frederick"),
+    Code::fixed("G", "George"),
+    Code::fixed("H", "Harry"),
+    Code::fixed("I", "Isaac"),
+    Code::fixed("J", "Jack"),
+    Code::fixed("K", "King"),
+    Code::fixed("L", "London"),
+    Code::fixed("M", "Mary"),
+    Code::fixed("N", "Nellie"),
+    Code::fixed("O", "Oliver"),
+    Code::fixed("P", "Peter"),
+    Code::fixed("Q", "Queen"),
+    Code::fixed("R", "Robert"),
+    Code::fixed("S", "Samuel"),
+    Code::fixed("T", "Tommy"),
+    Code::fixed("U", "Uncle"),
+    Code::fixed("V", "Victor"),
+    Code::fixed("W", "William"),
+    Code::fixed("X", "X-ray"),
+    Code::fixed("Y", "Yellow"),
+    Code::fixed("Z", "Zebra"),
+];
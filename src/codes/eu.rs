@@ -1,50 +1,54 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Eu {
-    codes: Vec<Code>,
-}
+pub struct Eu;
 
 impl Eu {
     pub fn new() -> Self {
-        Eu {
-            codes: phonetic_codes(),
-        }
+        Eu
     }
 }
 
 impl PhoneticAlphabet for Eu {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "eu"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Amerika"),
-        Code::new('B', "Baltimore"),
-        Code::new('C', "Canada"),
-        Code::new('D', "Dänemark"),
-        Code::new('E', "England"),
-        Code::new('F', "Frankreich"),
-        Code::new('G', "Guatemala"),
-        Code::new('H', "Honolulu"),
-        Code::new('I', "Italien"),
-        Code::new('J', "Japan"),
-        Code::new('K', "Kilowatt"),
-        Code::new('L', "Luxemburg"),
-        Code::new('M', "Mexico"),
-        Code::new('N', "Norwegen"),
-        Code::new('O', "Ontario"),
-        Code::new('P', "Portugal"),
-        Code::new('Q', "Québec"),
-        Code::new('R', "Radio"),
-        Code::new('S', "Santiago"),
-        Code::new('T', "Texas"),
-        Code::new('U', "Uruguay"),
-        Code::new('V', "Venezuela"),
-        Code::new('W', "Washington"),
-        Code::new('X', "Xylophon"),
-        Code::new('Y', "Yokohama"),
-        Code::new('Z', "Zebra"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("European Spelling Alphabet (Place Names)")
+            .with_language("de")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Amerika"),
+    Code::fixed("B", "Baltimore"),
+    Code::fixed("C", "Canada"),
+    Code::fixed("D", "Dänemark"),
+    Code::fixed("E", "England"),
+    Code::fixed("F", "Frankreich"),
+    Code::fixed("G", "Guatemala"),
+    Code::fixed("H", "Honolulu"),
+    Code::fixed("I", "Italien"),
+    Code::fixed("J", "Japan"),
+    Code::fixed("K", "Kilowatt"),
+    Code::fixed("L", "Luxemburg"),
+    Code::fixed("M", "Mexico"),
+    Code::fixed("N", "Norwegen"),
+    Code::fixed("O", "Ontario"),
+    Code::fixed("P", "Portugal"),
+    Code::fixed("Q", "Québec"),
+    Code::fixed("R", "Radio"),
+    Code::fixed("S", "Santiago"),
+    Code::fixed("T", "Texas"),
+    Code::fixed("U", "Uruguay"),
+    Code::fixed("V", "Venezuela"),
+    Code::fixed("W", "Washington"),
+    Code::fixed("X", "Xylophon"),
+    Code::fixed("Y", "Yokohama"),
+    Code::fixed("Z", "Zebra"),
+];
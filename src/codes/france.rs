@@ -1,50 +1,55 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct France {
-    codes: Vec<Code>,
-}
+pub struct France;
 
 impl France {
     pub fn new() -> Self {
-        France {
-            codes: phonetic_codes(),
-        }
+        France
     }
 }
 
 impl PhoneticAlphabet for France {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "france"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Anatole"),
-        Code::new('B', "Berthe"),
-        Code::new('C', "Célestine"),
-        Code::new('D', "Désiré"),
-        Code::new('E', "Eugène"),
-        Code::new('F', "François"),
-        Code::new('G', "Gaston"),
-        Code::new('H', "Henri"),
-        Code::new('I', "Irma"),
-        Code::new('J', "Joseph"),
-        Code::new('K', "Kléber"),
-        Code::new('L', "Louis"),
-        Code::new('M', "Marcel"),
-        Code::new('N', "Nicolas"),
-        Code::new('O', "Oscar"),
-        Code::new('P', "Pierre"),
-        Code::new('Q', "Quintal"),
-        Code::new('R', "Raoul"),
-        Code::new('S', "Suzanne"),
-        Code::new('T', "Thérèse"),
-        Code::new('U', "Ursule"),
-        Code::new('V', "Victor"),
-        Code::new('W', "William"),
-        Code::new('X', "Xavier"),
-        Code::new('Y', "Yvonne"),
-        Code::new('Z', "Zoé"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("French Spelling Alphabet")
+            .with_language("fr")
+            .with_region("FR")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Anatole"),
+    Code::fixed("B", "Berthe"),
+    Code::fixed("C", "Célestine"),
+    Code::fixed("D", "Désiré"),
+    Code::fixed("E", "Eugène"),
+    Code::fixed("F", "François"),
+    Code::fixed("G", "Gaston"),
+    Code::fixed("H", "Henri"),
+    Code::fixed("I", "Irma"),
+    Code::fixed("J", "Joseph"),
+    Code::fixed("K", "Kléber"),
+    Code::fixed("L", "Louis"),
+    Code::fixed("M", "Marcel"),
+    Code::fixed("N", "Nicolas"),
+    Code::fixed("O", "Oscar"),
+    Code::fixed("P", "Pierre"),
+    Code::fixed("Q", "Quintal"),
+    Code::fixed("R", "Raoul"),
+    Code::fixed("S", "Suzanne"),
+    Code::fixed("T", "Thérèse"),
+    Code::fixed("U", "Ursule"),
+    Code::fixed("V", "Victor"),
+    Code::fixed("W", "William"),
+    Code::fixed("X", "Xavier"),
+    Code::fixed("Y", "Yvonne"),
+    Code::fixed("Z", "Zoé"),
+];
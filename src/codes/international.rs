@@ -1,50 +1,57 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct International {
-    codes: Vec<Code>,
-}
+pub struct International;
 
 impl International {
     pub fn new() -> Self {
-        International {
-            codes: phonetic_codes(),
-        }
+        International
     }
 }
 
 impl PhoneticAlphabet for International {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "international"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Amsterdam"),
-        Code::new('B', "Baltimore"),
-        Code::new('C', "Casablanca"),
-        Code::new('D', "Danemark"),
-        Code::new('E', "Edison"),
-        Code::new('F', "Florida"),
-        Code::new('G', "Gallipoli"),
-        Code::new('H', "Havanna"),
-        Code::new('I', "Italia"),
-        Code::new('J', "Jerusalem"),
-        Code::new('K', "Kilogram"),
-        Code::new('L', "Liverpool"),
-        Code::new('M', "Madagaskar"),
-        Code::new('N', "New York"),
-        Code::new('O', "Oslo"),
-        Code::new('P', "Paris"),
-        Code::new('Q', "Québec"),
-        Code::new('R', "Roma"),
-        Code::new('S', "Santiago"),
-        Code::new('T', "Tripoli"),
-        Code::new('U', "Uppsala"),
-        Code::new('V', "Valencia"),
-        Code::new('W', "Washington"),
-        Code::new('X', "Xanthippe"),
-        Code::new('Y', "Yokohama"),
-        Code::new('Z', "Zürich"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("International Spelling Alphabet (Place Names)")
+            .with_language("en")
+            .with_standard("ITU")
+            .with_year(1932)
+            .with_source("https://en.wikipedia.org/wiki/Spelling_alphabet")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Amsterdam"),
+    Code::fixed("B", "Baltimore"),
+    Code::fixed("C", "Casablanca"),
+    Code::fixed("D", "Danemark"),
+    Code::fixed("E", "Edison"),
+    Code::fixed("F", "Florida"),
+    Code::fixed("G", "Gallipoli"),
+    Code::fixed("H", "Havanna"),
+    Code::fixed("I", "Italia"),
+    Code::fixed("J", "Jerusalem"),
+    Code::fixed("K", "Kilogram"),
+    Code::fixed("L", "Liverpool"),
+    Code::fixed("M", "Madagaskar"),
+    Code::fixed("N", "New York"),
+    Code::fixed("O", "Oslo"),
+    Code::fixed("P", "Paris"),
+    Code::fixed("Q", "Québec"),
+    Code::fixed("R", "Roma"),
+    Code::fixed("S", "Santiago"),
+    Code::fixed("T", "Tripoli"),
+    Code::fixed("U", "Uppsala"),
+    Code::fixed("V", "Valencia"),
+    Code::fixed("W", "Washington"),
+    Code::fixed("X", "Xanthippe"),
+    Code::fixed("Y", "Yokohama"),
+    Code::fixed("Z", "Zürich"),
+];
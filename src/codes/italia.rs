@@ -1,50 +1,55 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Italia {
-    codes: Vec<Code>,
-}
+pub struct Italia;
 
 impl Italia {
     pub fn new() -> Self {
-        Italia {
-            codes: phonetic_codes(),
-        }
+        Italia
     }
 }
 
 impl PhoneticAlphabet for Italia {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "italia"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Ancona"),
-        Code::new('B', "Bari"),
-        Code::new('C', "Como"),
-        Code::new('D', "Domodossola"),
-        Code::new('E', "Empoli"),
-        Code::new('F', "Firenze"),
-        Code::new('G', "Genova"),
-        Code::new('H', "Hotel"),
-        Code::new('I', "Imola"),
-        Code::new('J', "Juventus"),
-        Code::new('K', "Kilometro"),
-        Code::new('L', "Livorno"),
-        Code::new('M', "Milano"),
-        Code::new('N', "Napoli"),
-        Code::new('O', "Otranto"),
-        Code::new('P', "Pisa"),
-        Code::new('Q', "Quadro"),
-        Code::new('R', "Romeo"),
-        Code::new('S', "Savona"),
-        Code::new('T', "Torino"),
-        Code::new('U', "Udine"),
-        Code::new('V', "Venezia"),
-        Code::new('W', "Vu Doppia"),
-        Code::new('X', "Xilofono"),
-        Code::new('Y', "Ipsilon"),
-        Code::new('Z', "Zara"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("Italian Spelling Alphabet")
+            .with_language("it")
+            .with_region("IT")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Ancona"),
+    Code::fixed("B", "Bari"),
+    Code::fixed("C", "Como"),
+    Code::fixed("D", "Domodossola"),
+    Code::fixed("E", "Empoli"),
+    Code::fixed("F", "Firenze"),
+    Code::fixed("G", "Genova"),
+    Code::fixed("H", "Hotel"),
+    Code::fixed("I", "Imola"),
+    Code::fixed("J", "Juventus"),
+    Code::fixed("K", "Kilometro"),
+    Code::fixed("L", "Livorno"),
+    Code::fixed("M", "Milano"),
+    Code::fixed("N", "Napoli"),
+    Code::fixed("O", "Otranto"),
+    Code::fixed("P", "Pisa"),
+    Code::fixed("Q", "Quadro"),
+    Code::fixed("R", "Romeo"),
+    Code::fixed("S", "Savona"),
+    Code::fixed("T", "Torino"),
+    Code::fixed("U", "Udine"),
+    Code::fixed("V", "Venezia"),
+    Code::fixed("W", "Vu Doppia"),
+    Code::fixed("X", "Xilofono"),
+    Code::fixed("Y", "Ipsilon"),
+    Code::fixed("Z", "Zara"),
+];
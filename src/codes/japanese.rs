@@ -1,82 +1,288 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Japanese {
-    codes: Vec<Code>,
-}
+pub struct Japanese;
 
 impl Japanese {
     pub fn new() -> Self {
-        Japanese {
-            codes: phonetic_codes(),
-        }
+        Japanese
     }
 }
 
 impl PhoneticAlphabet for Japanese {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "japanese"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(phonetic_codes())
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("Japanese Radiotelephony Spelling Alphabet (和文通話表)")
+            .with_language("ja")
+            .with_region("JP")
+            .with_standard("無線局運用規則 別表第五号")
+            .with_source("https://ja.wikipedia.org/wiki/和文通話表")
     }
 }
 
+/// Voiced kana (with dakuten) and their base kana.
+const VOICED: [(char, char); 21] = [
+    ('が', 'か'), ('ぎ', 'き'), ('ぐ', 'く'), ('げ', 'け'), ('ご', 'こ'),
+    ('ざ', 'さ'), ('じ', 'し'), ('ず', 'す'), ('ぜ', 'せ'), ('ぞ', 'そ'),
+    ('だ', 'た'), ('ぢ', 'ち'), ('づ', 'つ'), ('で', 'て'), ('ど', 'と'),
+    ('ば', 'は'), ('び', 'ひ'), ('ぶ', 'ふ'), ('べ', 'へ'), ('ぼ', 'ほ'),
+    ('ゔ', 'う'),
+];
+
+/// Semi-voiced kana (with handakuten) and their base kana.
+const SEMI_VOICED: [(char, char); 5] = [
+    ('ぱ', 'は'), ('ぴ', 'ひ'), ('ぷ', 'ふ'), ('ぺ', 'へ'), ('ぽ', 'ほ'),
+];
+
+/// Small kana and their base kana.
+const SMALL: [(char, char); 12] = [
+    ('ぁ', 'あ'), ('ぃ', 'い'), ('ぅ', 'う'), ('ぇ', 'え'), ('ぉ', 'お'),
+    ('っ', 'つ'), ('ゃ', 'や'), ('ゅ', 'ゆ'), ('ょ', 'よ'), ('ゎ', 'わ'),
+    ('ゕ', 'か'), ('ゖ', 'け'),
+];
+
+/// Suffixes of the codes for the voiced kana, the first one is used for encoding.
+const VOICED_SUFFIXES: [&str; 3] = ["に濁点", "にだくてん", "に点々"];
+/// Suffixes of the codes for the semi-voiced kana, the first one is used for encoding.
+const SEMI_VOICED_SUFFIXES: [&str; 3] = ["に半濁点", "にはんだくてん", "に丸"];
+/// Prefixes of the codes for the small kana, the first one is used for encoding.
+const SMALL_PREFIXES: [&str; 3] = ["小さい", "ちいさい", "小さな"];
+
+/// Readings of the key words in the basic codes, such as "あさひ" for "朝日" in "朝日のア".
+const READINGS: [(char, &str); 48] = [
+    ('あ', "あさひ"), ('い', "いろは"), ('う', "うえの"), ('え', "えいご"), ('お', "おおさか"),
+    ('か', "かわせ"), ('き', "きって"), ('く', "くらぶ"), ('け', "けしき"), ('こ', "こども"),
+    ('さ', "さくら"), ('し', "しんぶん"), ('す', "すずめ"), ('せ', "せかい"), ('そ', "そろばん"),
+    ('た', "たばこ"), ('ち', "ちどり"), ('つ', "つるかめ"), ('て', "てがみ"), ('と', "とうきょう"),
+    ('な', "なごや"), ('に', "にっぽん"), ('ぬ', "ぬまづ"), ('ね', "ねずみ"), ('の', "のはら"),
+    ('は', "はがき"), ('ひ', "ひこうき"), ('ふ', "ふじさん"), ('へ', "へいわ"), ('ほ', "ほけん"),
+    ('ま', "まっち"), ('み', "みかさ"), ('む', "むせん"), ('め', "めいじ"), ('も', "もみじ"),
+    ('や', "やまと"), ('ゆ', "ゆみや"), ('よ', "よしの"),
+    ('ら', "らじお"), ('り', "りんご"), ('る', "るすい"), ('れ', "れんげ"), ('ろ', "ろーま"),
+    ('わ', "わらび"), ('ゐ', "ゐど"), ('ゑ', "かぎのある"), ('を', "おわり"), ('ん', "おしまい"),
+];
+
+/// Builds the phonetic codes of the Japanese kana.
+/// The codes of the voiced, semi-voiced, and small kana are derived from the basic hiragana codes,
+/// e.g., "為替のカに濁点" for 'が', "葉書のハに半濁点" for 'ぱ', and "小さい大和のヤ" for 'ゃ'.
+/// Then, the katakana are mapped onto the same codes as the corresponding hiragana.
+///
+/// In decoding, each code accepts the key word alone (e.g., "朝日"), the full phrase (e.g., "朝日のア"),
+/// and their readings in hiragana and katakana (e.g., "あさひのあ" and "アサヒ") as the aliases.
 fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('あ', "朝日のア"),
-        Code::new('い', "いろはのイ"),
-        Code::new('う', "上野のウ"),
-        Code::new('え', "英語のエ"),
-        Code::new('お', "大阪のオ"),
-        Code::new('か', "為替のカ"),
-        Code::new('き', "切手のキ"),
-        Code::new('く', "クラブのク"),
-        Code::new('け', "景色のケ"),
-        Code::new('こ', "子どものコ"),
-        Code::new('さ', "桜のサ"),
-        Code::new('し', "新聞のシ"),
-        Code::new('す', "すずめのス"),
-        Code::new('せ', "世界のセ"),
-        Code::new('そ', "そろばんのソ"),
-        Code::new('た', "たばこのタ"),
-        Code::new('ち', "ちどりのチ"),
-        Code::new('つ', "つるかめのツ"),
-        Code::new('て', "手紙のテ"),
-        Code::new('と', "東京のト"),
-        Code::new('な', "名古屋のナ"),
-        Code::new('に', "日本の二"),
-        Code::new('ぬ', "沼津のヌ"),
-        Code::new('ね', "ねずみのネ"),
-        Code::new('の', "野原のノ"),
-        Code::new('は', "葉書のハ"),
-        Code::new('ひ', "飛行機のヒ"),
-        Code::new('ふ', "富士山のフ"),
-        Code::new('へ', "平和のヘ"),
-        Code::new('ほ', "保険のホ"),
-        Code::new('ま', "マッチのマ"),
-        Code::new('み', "三笠のミ"),
-        Code::new('む', "無線のム"),
-        Code::new('め', "明治のメ"),
-        Code::new('も', "もみじのモ"),
-        Code::new('や', "大和のヤ"),
-        Code::new('ゆ', "弓矢のユ"),
-        Code::new('よ', "吉野のヨ"),
-        Code::new('ら', "ラジオのラ"),
-        Code::new('り', "りんごのリ"),
-        Code::new('る', "るすいのル"),
-        Code::new('れ', "れんげのレ"),
-        Code::new('ろ', "ローマのロ"),
-        Code::new('わ', "わらびのワ"),
-        Code::new('ゐ', "ゐどのヰ"),
-        Code::new('ゑ', "かぎのあるヱ"),
-        Code::new('を', "尾張のヲ"),
-        Code::new('ん', "おしまいのン"),
-        Code::new('0', "マル"),
-        Code::new('1', "ヒト"),
-        Code::new('2', "フタ"),
-        Code::new('3', "サン"),
-        Code::new('4', "ヨン"),
-        Code::new('5', "ゴ"),
-        Code::new('6', "ロク"),
-        Code::new('7', "ナナ"),
-        Code::new('8', "ハチ"),
-        Code::new('9', "キュウ"),
-    ]
+    let mut codes = BASIC_CODES.iter().cloned()
+        .map(|code| {
+            let aliases = basic_aliases(&code);
+            code.with_aliases(&aliases)
+        })
+        .collect::<Vec<_>>();
+    let derived = derived_codes(&codes);
+    codes.extend(derived);
+    codes.push(Code::new('ー', "長音").with_aliases(&["ちょうおん", "チョウオン", "伸ばし棒", "のばしぼう"]));
+    let katakana = codes.iter()
+        .filter_map(|code| to_katakana(code.letter()).map(|k| Code::new(k, code.code()).with_aliases(&code.aliases().collect::<Vec<_>>())))
+        .collect::<Vec<_>>();
+    codes.extend(katakana);
+    codes
+}
+
+/// Returns the aliases of the given basic code, the key word alone, the full phrase, and their kana variants.
+fn basic_aliases(code: &Code) -> Vec<String> {
+    let phrase = code.code();
+    let Some(reading) = READINGS.iter().find(|(c, _)| *c == code.letter()).map(|(_, r)| *r) else {
+        return Vec::new(); // digits have no aliases.
+    };
+    let prefix = phrase.strip_suffix(|_: char| true).unwrap_or_default();
+    let key = prefix.strip_suffix('の').unwrap_or(prefix);
+    let reading_prefix = prefix.replacen(key, reading, 1);
+    let letter = code.letter();
+    let kata_letter = to_katakana(letter).unwrap_or(letter);
+    let candidates = [
+        key.to_string(),
+        reading.to_string(),
+        format!("{prefix}{letter}"),
+        format!("{prefix}{kata_letter}"),
+        format!("{reading_prefix}{letter}"),
+        format!("{reading_prefix}{kata_letter}"),
+    ];
+    let mut aliases = Vec::new();
+    for candidate in candidates {
+        for variant in [candidate.clone(), katakana_string(&candidate), hiragana_string(&candidate)] {
+            if variant != phrase && !aliases.contains(&variant) {
+                aliases.push(variant);
+            }
+        }
+    }
+    aliases
+}
+
+fn derived_codes(basics: &[Code]) -> Vec<Code> {
+    let base_of = |base: char| basics.iter()
+        .find(|code| code.letter() == base)
+        .expect("the base kana must be in the basic codes");
+    let voiced = VOICED.iter()
+        .map(|&(letter, base)| derive(letter, base_of(base), |s| VOICED_SUFFIXES.map(|suffix| format!("{s}{suffix}"))));
+    let semi_voiced = SEMI_VOICED.iter()
+        .map(|&(letter, base)| derive(letter, base_of(base), |s| SEMI_VOICED_SUFFIXES.map(|suffix| format!("{s}{suffix}"))));
+    let small = SMALL.iter()
+        .map(|&(letter, base)| derive(letter, base_of(base), |s| SMALL_PREFIXES.map(|prefix| format!("{prefix}{s}"))));
+    voiced.chain(semi_voiced).chain(small).collect()
+}
+
+/// Derives the code of the given letter from the given base code, by decorating all spellings of the base code.
+/// The decorated spellings are also accepted in all hiragana or all katakana.
+/// The first decoration of the primary spelling of the base becomes the primary spelling of the derived code.
+fn derive(letter: char, base: &Code, decorate: impl Fn(&str) -> [String; 3]) -> Code {
+    let mut spellings = base.spellings().flat_map(&decorate);
+    let primary = spellings.next().unwrap_or_default();
+    let mut aliases = Vec::new();
+    for spelling in spellings {
+        for variant in [katakana_string(&spelling), hiragana_string(&spelling), spelling] {
+            if variant != primary && !aliases.contains(&variant) {
+                aliases.push(variant);
+            }
+        }
+    }
+    Code::new(letter, primary).with_aliases(&aliases)
+}
+
+/// Converts the given hiragana into the corresponding katakana, returns `None` if the given character is not a hiragana.
+pub(crate) fn to_katakana(c: char) -> Option<char> {
+    match c {
+        '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60),
+        _ => None,
+    }
+}
+
+/// Converts the given katakana into the corresponding hiragana, returns `None` if the given character is not a katakana.
+pub(crate) fn to_hiragana(c: char) -> Option<char> {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60),
+        _ => None,
+    }
+}
+
+fn katakana_string(s: &str) -> String {
+    s.chars().map(|c| to_katakana(c).unwrap_or(c)).collect()
+}
+
+fn hiragana_string(s: &str) -> String {
+    s.chars().map(|c| to_hiragana(c).unwrap_or(c)).collect()
+}
+
+/// The basic codes of the hiragana and the digits, which the other codes are derived from.
+static BASIC_CODES: &[Code] = &[
+    Code::fixed("あ", "朝日のア"),
+    Code::fixed("い", "いろはのイ"),
+    Code::fixed("う", "上野のウ"),
+    Code::fixed("え", "英語のエ"),
+    Code::fixed("お", "大阪のオ"),
+    Code::fixed("か", "為替のカ"),
+    Code::fixed("き", "切手のキ"),
+    Code::fixed("く", "クラブのク"),
+    Code::fixed("け", "景色のケ"),
+    Code::fixed("こ", "子どものコ"),
+    Code::fixed("さ", "桜のサ"),
+    Code::fixed("し", "新聞のシ"),
+    Code::fixed("す", "すずめのス"),
+    Code::fixed("せ", "世界のセ"),
+    Code::fixed("そ", "そろばんのソ"),
+    Code::fixed("た", "たばこのタ"),
+    Code::fixed("ち", "ちどりのチ"),
+    Code::fixed("つ", "つるかめのツ"),
+    Code::fixed("て", "手紙のテ"),
+    Code::fixed("と", "東京のト"),
+    Code::fixed("な", "名古屋のナ"),
+    Code::fixed("に", "日本の二"),
+    Code::fixed("ぬ", "沼津のヌ"),
+    Code::fixed("ね", "ねずみのネ"),
+    Code::fixed("の", "野原のノ"),
+    Code::fixed("は", "葉書のハ"),
+    Code::fixed("ひ", "飛行機のヒ"),
+    Code::fixed("ふ", "富士山のフ"),
+    Code::fixed("へ", "平和のヘ"),
+    Code::fixed("ほ", "保険のホ"),
+    Code::fixed("ま", "マッチのマ"),
+    Code::fixed("み", "三笠のミ"),
+    Code::fixed("む", "無線のム"),
+    Code::fixed("め", "明治のメ"),
+    Code::fixed("も", "もみじのモ"),
+    Code::fixed("や", "大和のヤ"),
+    Code::fixed("ゆ", "弓矢のユ"),
+    Code::fixed("よ", "吉野のヨ"),
+    Code::fixed("ら", "ラジオのラ"),
+    Code::fixed("り", "りんごのリ"),
+    Code::fixed("る", "るすいのル"),
+    Code::fixed("れ", "れんげのレ"),
+    Code::fixed("ろ", "ローマのロ"),
+    Code::fixed("わ", "わらびのワ"),
+    Code::fixed("ゐ", "ゐどのヰ"),
+    Code::fixed("ゑ", "かぎのあるヱ"),
+    Code::fixed("を", "尾張のヲ"),
+    Code::fixed("ん", "おしまいのン"),
+    Code::fixed("0", "マル"),
+    Code::fixed("1", "ヒト"),
+    Code::fixed("2", "フタ"),
+    Code::fixed("3", "サン"),
+    Code::fixed("4", "ヨン"),
+    Code::fixed("5", "ゴ"),
+    Code::fixed("6", "ロク"),
+    Code::fixed("7", "ナナ"),
+    Code::fixed("8", "ハチ"),
+    Code::fixed("9", "キュウ"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_of(codes: &[Code], letter: char) -> Option<&str> {
+        codes.iter().find(|code| code.letter() == letter).map(|code| code.code())
+    }
+
+    #[test]
+    fn test_phonetic_codes() {
+        let codes = phonetic_codes();
+        assert_eq!(code_of(&codes, 'ア'), Some("朝日のア"));
+        assert_eq!(code_of(&codes, 'ガ'), Some("為替のカに濁点"));
+        assert_eq!(code_of(&codes, 'ぱ'), Some("葉書のハに半濁点"));
+        assert_eq!(code_of(&codes, 'ゃ'), Some("小さい大和のヤ"));
+        assert_eq!(code_of(&codes, 'ッ'), Some("小さいつるかめのツ"));
+        assert_eq!(code_of(&codes, 'ー'), Some("長音"));
+        assert_eq!(code_of(&codes, 'ヴ'), Some("上野のウに濁点"));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let codes = phonetic_codes();
+        let aliases = |letter: char| codes.iter().find(|code| code.letter() == letter)
+            .map(|code| code.aliases().map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let a = aliases('あ');
+        for expected in ["朝日", "あさひ", "アサヒ", "朝日のあ", "あさひのあ", "アサヒノア"] {
+            assert!(a.contains(&expected.to_string()), "{expected} is not found in {a:?}");
+        }
+        assert!(aliases('ゑ').contains(&"かぎのあるゑ".to_string()));
+        assert!(aliases('が').contains(&"かわせにだくてん".to_string()));
+        assert!(aliases('ゃ').contains(&"ちいさいやまと".to_string()));
+        assert!(aliases('0').is_empty());
+    }
+
+    #[test]
+    fn test_unique_spellings() {
+        let codes = phonetic_codes();
+        let hiragana = codes.iter().filter(|code| to_hiragana(code.letter()).is_none()).collect::<Vec<_>>();
+        let mut spellings = hiragana.iter().flat_map(|code| code.spellings()).collect::<Vec<_>>();
+        let count = spellings.len();
+        spellings.sort();
+        spellings.dedup();
+        assert_eq!(spellings.len(), count);
+    }
 }
@@ -1,63 +1,72 @@
-use crate::{Code, PhoneticAlphabet};
+use std::borrow::Cow;
 
-pub struct Nato {
-    codes: Vec<Code>,
-}
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
+
+pub struct Nato;
 
 impl Nato {
     pub fn new() -> Self {
-        Nato {
-            codes: phonetic_codes(),
-        }
+        Nato
     }
 }
 
 impl PhoneticAlphabet for Nato {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "nato"
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Alpha"),
-        Code::new('\u{00C4}', "Ärger"),
-        Code::new('B', "Bravo"),
-        Code::new('C', "Charlie"),
-        Code::new('D', "Delta"),
-        Code::new('E', "Echo"),
-        Code::new('F', "Foxtrot"),
-        Code::new('G', "Golf"),
-        Code::new('H', "Hotel"),
-        Code::new('I', "India"),
-        Code::new('J', "Juliett"),
-        Code::new('K', "Kilo"),
-        Code::new('L', "Lima"),
-        Code::new('M', "Mike"),
-        Code::new('N', "November"),
-        Code::new('O', "Oscar"),
-        Code::new('\u{00D6}', "Öser"),
-        Code::new('P', "Papa"),
-        Code::new('Q', "Quebec"),
-        Code::new('R', "Romeo"),
-        Code::new('S', "Sierra"),
-        Code::new('T', "Tango"),
-        Code::new('U', "Uniform"),
-        Code::new('\u{00DC}', "Übel"),
-        Code::new('V', "Victor"),
-        Code::new('W', "Whiskey"),
-        Code::new('X', "X-ray"),
-        Code::new('Y', "Yankee"),
-        Code::new('Z', "Zulu"),
-        Code::new('0', "Zero"),
-        Code::new('1', "One"),
-        Code::new('2', "Two"),
-        Code::new('3', "Three"),
-        Code::new('4', "Four"),
-        Code::new('5', "Five"),
-        Code::new('6', "Six"),
-        Code::new('7', "Seven"),
-        Code::new('8', "Eight"),
-        Code::new('9', "Nine"),
-    ]
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("NATO Phonetic Alphabet")
+            .with_language("en")
+            .with_standard("ICAO / NATO")
+            .with_year(1956)
+            .with_source("https://www.nato.int/content/dam/nato/webready/news/2010-2019/2017/12/21/20180111_nato-alphabet-sign-signal.pdf")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed_with_aliases("A", "Alpha", &[Cow::Borrowed("Alfa")]),
+    Code::fixed("\u{00C4}", "Ärger"),
+    Code::fixed("B", "Bravo"),
+    Code::fixed("C", "Charlie"),
+    Code::fixed("D", "Delta"),
+    Code::fixed("E", "Echo"),
+    Code::fixed("F", "Foxtrot"),
+    Code::fixed("G", "Golf"),
+    Code::fixed("H", "Hotel"),
+    Code::fixed("I", "India"),
+    Code::fixed_with_aliases("J", "Juliett", &[Cow::Borrowed("Juliet")]),
+    Code::fixed("K", "Kilo"),
+    Code::fixed("L", "Lima"),
+    Code::fixed("M", "Mike"),
+    Code::fixed("N", "November"),
+    Code::fixed("O", "Oscar"),
+    Code::fixed("\u{00D6}", "Öser"),
+    Code::fixed("P", "Papa"),
+    Code::fixed("Q", "Quebec"),
+    Code::fixed("R", "Romeo"),
+    Code::fixed("S", "Sierra"),
+    Code::fixed("T", "Tango"),
+    Code::fixed("U", "Uniform"),
+    Code::fixed("\u{00DC}", "Übel"),
+    Code::fixed("V", "Victor"),
+    Code::fixed_with_aliases("W", "Whiskey", &[Cow::Borrowed("Whisky")]),
+    Code::fixed_with_aliases("X", "X-ray", &[Cow::Borrowed("Xray")]),
+    Code::fixed("Y", "Yankee"),
+    Code::fixed("Z", "Zulu"),
+    Code::fixed("0", "Zero"),
+    Code::fixed("1", "One"),
+    Code::fixed("2", "Two"),
+    Code::fixed("3", "Three"),
+    Code::fixed("4", "Four"),
+    Code::fixed("5", "Five"),
+    Code::fixed("6", "Six"),
+    Code::fixed("7", "Seven"),
+    Code::fixed("8", "Eight"),
+    Code::fixed_with_aliases("9", "Nine", &[Cow::Borrowed("Niner")]),
+];
@@ -1,51 +1,57 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Netherlands {
-    codes: Vec<Code>,
-}
+pub struct Netherlands;
 
 impl Netherlands {
     pub fn new() -> Self {
-        Netherlands {
-            codes: phonetic_codes(),
-        }
+        Netherlands
     }
 }
 
 impl PhoneticAlphabet for Netherlands {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "netherlands"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Anna"),
-        Code::new('B', "Bernard"),
-        Code::new('C', "Cornelis"),
-        Code::new('D', "Dirk"),
-        Code::new('E', "Eduard"),
-        Code::new('F', "Ferdinand"),
-        Code::new('G', "Gerard"),
-        Code::new('H', "Hendrik"),
-        Code::new('I', "Izaak"),
-        Code::new('J', "Jan"),
-        Code::new('K', "Karel"),
-        Code::new('L', "Lodewijk"),
-        Code::new('M', "Marie"),
-        Code::new('N', "Nico"),
-        Code::new('O', "Otto"),
-        Code::new('P', "Pieter"),
-        Code::new('Q', "Quotiënt"),
-        Code::new('R', "Rudolf"),
-        Code::new('S', "Simon"),
-        Code::new('T', "Teunis"),
-        Code::new('U', "Utrecht"),
-        Code::new('V', "Victor"),
-        Code::new('W', "Willem"),
-        Code::new('X', "Xanthippe"),
-        Code::new('Y', "Ypsilon"),
-        Code::new('\u{0132}', "IJmuiden"),
-        Code::new('Z', "Zaandam"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("Dutch Spelling Alphabet")
+            .with_language("nl")
+            .with_region("NL")
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Anna"),
+    Code::fixed("B", "Bernard"),
+    Code::fixed("C", "Cornelis"),
+    Code::fixed("D", "Dirk"),
+    Code::fixed("E", "Eduard"),
+    Code::fixed("F", "Ferdinand"),
+    Code::fixed("G", "Gerard"),
+    Code::fixed("H", "Hendrik"),
+    Code::fixed("I", "Izaak"),
+    Code::fixed("J", "Jan"),
+    Code::fixed("K", "Karel"),
+    Code::fixed("L", "Lodewijk"),
+    Code::fixed("M", "Marie"),
+    Code::fixed("N", "Nico"),
+    Code::fixed("O", "Otto"),
+    Code::fixed("P", "Pieter"),
+    Code::fixed("Q", "Quotiënt"),
+    Code::fixed("R", "Rudolf"),
+    Code::fixed("S", "Simon"),
+    Code::fixed("T", "Teunis"),
+    Code::fixed("U", "Utrecht"),
+    Code::fixed("V", "Victor"),
+    Code::fixed("W", "Willem"),
+    Code::fixed("X", "Xanthippe"),
+    Code::fixed("Y", "Ypsilon"),
+    Code::fixed("IJ", "IJmuiden"),
+    Code::fixed("\u{0132}", "IJmuiden"),
+    Code::fixed("Z", "Zaandam"),
+];
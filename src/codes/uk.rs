@@ -1,50 +1,56 @@
-use crate::{Code, PhoneticAlphabet};
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
 
-pub struct Uk {
-    codes: Vec<Code>,
-}
+pub struct Uk;
 
 impl Uk {
     pub fn new() -> Self {
-        Uk {
-            codes: phonetic_codes(),
-        }
+        Uk
     }
 }
 
 impl PhoneticAlphabet for Uk {
-    fn into_entries(self) -> impl Iterator<Item = Code> {
-        self.codes.into_iter()
+    fn name(&self) -> &str {
+        "uk"
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        Ok(CODES.to_vec())
     }
-}
 
-fn phonetic_codes() -> Vec<Code> {
-    vec![
-        Code::new('A', "Able"),
-        Code::new('B', "Baker"),
-        Code::new('C', "Charlie"),
-        Code::new('D', "Dog"),
-        Code::new('E', "Easy"),
-        Code::new('F', "Fox"),
-        Code::new('G', "George"),
-        Code::new('H', "How"),
-        Code::new('I', "Item"),
-        Code::new('J', "Jig"),
-        Code::new('K', "King"),
-        Code::new('L', "Love"),
-        Code::new('M', "Mike"),
-        Code::new('N', "Nan"),
-        Code::new('O', "Oboe"),
-        Code::new('P', "Peter"),
-        Code::new('Q', "Queen"),
-        Code::new('R', "Roger"),
-        Code::new('S', "Samuel"),
-        Code::new('T', "Tare"),
-        Code::new('U', "Uncle"),
-        Code::new('V', "Victor"),
-        Code::new('W', "William"),
-        Code::new('X', "X-ray"),
-        Code::new('Y', "Yoke"),
-        Code::new('Z', "Zebra"),
-    ]
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+            .with_display_name("British Phonetic Alphabet (Able Baker)")
+            .with_language("en")
+            .with_region("GB")
+            .with_year(1943)
+    }
 }
+
+static CODES: &[Code] = &[
+    Code::fixed("A", "Able"),
+    Code::fixed("B", "Baker"),
+    Code::fixed("C", "Charlie"),
+    Code::fixed("D", "Dog"),
+    Code::fixed("E", "Easy"),
+    Code::fixed("F", "Fox"),
+    Code::fixed("G", "George"),
+    Code::fixed("H", "How"),
+    Code::fixed("I", "Item"),
+    Code::fixed("J", "Jig"),
+    Code::fixed("K", "King"),
+    Code::fixed("L", "Love"),
+    Code::fixed("M", "Mike"),
+    Code::fixed("N", "Nan"),
+    Code::fixed("O", "Oboe"),
+    Code::fixed("P", "Peter"),
+    Code::fixed("Q", "Queen"),
+    Code::fixed("R", "Roger"),
+    Code::fixed("S", "Samuel"),
+    Code::fixed("T", "Tare"),
+    Code::fixed("U", "Uncle"),
+    Code::fixed("V", "Victor"),
+    Code::fixed("W", "William"),
+    Code::fixed("X", "X-ray"),
+    Code::fixed("Y", "Yoke"),
+    Code::fixed("Z", "Zebra"),
+];
@@ -0,0 +1,46 @@
+use crate::{Code, Error, Metadata, PhoneticAlphabet};
+
+/// A phonetic alphabet based on another alphabet with some substitutions for specific characters.
+pub struct Variant<B: PhoneticAlphabet> {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    base: B,
+    substitutions: &'static [Code],
+    metadata: Metadata,
+}
+
+impl<B: PhoneticAlphabet> Variant<B> {
+    pub fn new(name: &'static str, aliases: &'static [&'static str], base: B, substitutions: &'static [Code]) -> Self {
+        Variant { name, aliases, base, substitutions, metadata: Metadata::default() }
+    }
+
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+impl<B: PhoneticAlphabet> PhoneticAlphabet for Variant<B> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
+    fn codes(&self) -> Result<Vec<Code>, Error> {
+        let mut codes = self.base.codes()?;
+        for substitution in self.substitutions {
+            match codes.iter_mut().find(|code| code.letters() == substitution.letters()) {
+                Some(code) => *code = substitution.clone(),
+                None => codes.push(substitution.clone()),
+            }
+        }
+        Ok(codes)
+    }
+
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+use crate::Matching;
+
+/// Options for decoding the phonetic codes with [`Codes::decode_report`](crate::Codes::decode_report).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    matching: Matching,
+    strict: bool,
+}
+
+impl DecodeOptions {
+    /// Sets the matching mode for finding the phonetic codes (see [`Matching`]).
+    pub fn matching(mut self, matching: Matching) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Sets the strict mode.
+    /// In the strict mode, decoding fails with [`Error::Decode`](crate::Error::Decode) if any token is not recognized.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the matching mode for finding the phonetic codes.
+    pub fn matching_mode(&self) -> Matching {
+        self.matching
+    }
+
+    /// Returns `true` if the strict mode is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
+
+/// Represents a token which does not match any phonetic code in decoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownToken {
+    /// The index of the token in the given items (0-origin).
+    pub index: usize,
+    /// The text of the token.
+    pub text: String,
+}
+
+impl Display for UnknownToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "token {} '{}': Unknown phonetic code", self.index + 1, self.text)
+    }
+}
+
+/// Represents a token and its decoded letters in decoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedToken {
+    /// The index of the token in the given items (0-origin).
+    pub index: usize,
+    /// The text of the token.
+    pub text: String,
+    /// The decoded letters, a space for the blank token (the word break), and `None` for the unrecognized token.
+    pub letters: Option<String>,
+}
+
+/// The result of decoding the phonetic codes.
+/// The unrecognized tokens are replaced with a space character in the decoded text, same as [`Codes::decode`](crate::Codes::decode),
+/// and are listed in the report, so that they are distinguishable from the word breaks (the blank tokens).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodeReport {
+    text: String,
+    tokens: Vec<DecodedToken>,
+    unknowns: Vec<UnknownToken>,
+}
+
+impl DecodeReport {
+    pub(crate) fn new(text: String, tokens: Vec<DecodedToken>, unknowns: Vec<UnknownToken>) -> Self {
+        DecodeReport { text, tokens, unknowns }
+    }
+
+    /// Returns the decoded text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns all of the tokens with their decoded letters, in order of the given items.
+    pub fn tokens(&self) -> &[DecodedToken] {
+        &self.tokens
+    }
+
+    /// Returns the tokens which do not match any phonetic code.
+    pub fn unknowns(&self) -> &[UnknownToken] {
+        &self.unknowns
+    }
+
+    /// Returns `true` if all of non-blank tokens are recognized.
+    pub fn is_complete(&self) -> bool {
+        self.unknowns.is_empty()
+    }
+
+    /// Consumes the report and returns the decoded text.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
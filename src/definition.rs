@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use toml::Spanned;
+
+use crate::codes::{base_error, remove_letters, Context};
+use crate::{alphabet, Code, Codes, Diagnostic, Error, Metadata};
+
+/// The syntax of the alphabet definition files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// The line format, such as "A, Alfa", with the `#@` headers.
+    Lines,
+    Toml,
+    Json,
+}
+
+impl Format {
+    /// Detects the format by the extension of the given path, or by the content if the extension is unknown.
+    pub(crate) fn detect(path: Option<&Path>, content: &[u8]) -> Format {
+        match path.and_then(Path::extension).and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("toml") => Format::Toml,
+            Some("json") => Format::Json,
+            Some("txt") => Format::Lines,
+            _ => Format::detect_content(content),
+        }
+    }
+
+    /// Detects the format by the first line except the blank lines and the comments.
+    /// The line format never starts with '{' nor '[', nor assigns a quoted string or an array to a bare key.
+    fn detect_content(content: &[u8]) -> Format {
+        let content = String::from_utf8_lossy(content);
+        let first = content.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+        if first.starts_with('{') {
+            Format::Json
+        } else if first.starts_with('[') {
+            Format::Toml
+        } else {
+            match first.split_once('=') {
+                Some((key, value)) if is_bare_key(key.trim()) && value.trim_start().starts_with(['"', '\'', '[', '{']) => Format::Toml,
+                _ => Format::Lines,
+            }
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Lines => write!(f, "line"),
+            Format::Toml => write!(f, "TOML"),
+            Format::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+fn is_bare_key(key: &str) -> bool {
+    key.len() > 1 && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Represents the alphabet definition in the structured formats (TOML and JSON).
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    /// The names of the phonetic codes to inherit the codes from, which are layered in order.
+    #[serde(default, skip_serializing_if = "Bases::is_none")]
+    base: Bases,
+    /// The files to include, relative to the definition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<String>,
+    /// The letters to remove from the bases and the included files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remove: Vec<String>,
+    #[serde(default, skip_serializing_if = "Header::is_empty")]
+    metadata: Header,
+    #[serde(default)]
+    codes: Vec<Entry>,
+}
+
+/// The base of the definition, which accepts a name or a list of names.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(untagged)]
+enum Bases {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Bases {
+    fn is_none(&self) -> bool {
+        matches!(self, Bases::None)
+    }
+
+    fn names(&self) -> &[String] {
+        match self {
+            Bases::None => &[],
+            Bases::One(name) => std::slice::from_ref(name),
+            Bases::Many(names) => names,
+        }
+    }
+}
+
+/// The metadata of the definition, in the same keys as the `#@` headers of the line format.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Header {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    standard: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    year: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+}
+
+impl Header {
+    fn is_empty(&self) -> bool {
+        self.display_name.is_none() && self.language.is_none() && self.region.is_none()
+            && self.standard.is_none() && self.year.is_none() && self.source.is_none()
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    letters: String,
+    code: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    /// The note is for the readers of the definition, such as the rationale of the code.
+    #[serde(default, rename = "note", skip_serializing_if = "Option::is_none")]
+    _note: Option<String>,
+}
+
+impl From<&Metadata> for Header {
+    fn from(metadata: &Metadata) -> Self {
+        Header {
+            display_name: metadata.display_name().map(String::from),
+            language: metadata.language().map(String::from),
+            region: metadata.region().map(String::from),
+            standard: metadata.standard().map(String::from),
+            year: metadata.year(),
+            source: metadata.source().map(String::from),
+        }
+    }
+}
+
+impl From<Header> for Metadata {
+    fn from(header: Header) -> Self {
+        let mut metadata = Metadata::default();
+        if let Some(display_name) = header.display_name {
+            metadata = metadata.with_display_name(display_name);
+        }
+        if let Some(language) = header.language {
+            metadata = metadata.with_language(language);
+        }
+        if let Some(region) = header.region {
+            metadata = metadata.with_region(region);
+        }
+        if let Some(standard) = header.standard {
+            metadata = metadata.with_standard(standard);
+        }
+        if let Some(year) = header.year {
+            metadata = metadata.with_year(year);
+        }
+        if let Some(source) = header.source {
+            metadata = metadata.with_source(source);
+        }
+        metadata
+    }
+}
+
+fn deserialize(content: &[u8], format: Format) -> Result<Definition, Diagnostic> {
+    let text = std::str::from_utf8(content)
+        .map_err(|e| {
+            let (line, column) = position_of(content, e.valid_up_to());
+            Diagnostic::new(line, column, "", "a readable text in UTF-8")
+        })?;
+    match format {
+        Format::Toml => toml::from_str(text)
+            .map_err(|e| {
+                let (line, column) = position_of(content, e.span().map(|span| span.start).unwrap_or_default());
+                Diagnostic::new(line, column, "", format!("a valid TOML definition ({})", e.message().trim()))
+            }),
+        _ => serde_json::from_str(text)
+            .map_err(|e| {
+                let message = e.to_string();
+                let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+                Diagnostic::new(e.line(), e.column().max(1), "", format!("a valid JSON definition ({message})"))
+            }),
+    }
+}
+
+/// Returns the line and the column (1-origin, in characters) of the given byte offset.
+fn position_of(content: &[u8], offset: usize) -> (usize, usize) {
+    let head = String::from_utf8_lossy(&content[..offset.min(content.len())]);
+    let line = head.matches('\n').count() + 1;
+    let column = head.rsplit('\n').next().map(|last| last.chars().count()).unwrap_or_default() + 1;
+    (line, column)
+}
+
+/// The byte offsets of the values in the definition, to locate the problems of the values in the diagnostics.
+/// Each offset points to the first character in the quotes of the string value.
+#[derive(Default)]
+struct Offsets {
+    remove: Vec<usize>,
+    codes: Vec<EntryOffsets>,
+}
+
+#[derive(Default)]
+struct EntryOffsets {
+    letters: usize,
+    code: usize,
+    aliases: Vec<usize>,
+}
+
+/// The values of the TOML definition with their spans, other keys are ignored.
+#[derive(Deserialize)]
+struct SpannedDefinition {
+    #[serde(default)]
+    remove: Vec<Spanned<String>>,
+    #[serde(default)]
+    codes: Vec<SpannedEntry>,
+}
+
+#[derive(Deserialize)]
+struct SpannedEntry {
+    letters: Spanned<String>,
+    code: Spanned<String>,
+    #[serde(default)]
+    aliases: Vec<Spanned<String>>,
+}
+
+/// The values of the JSON definition borrowed from the text, whose offsets are given by the addresses.
+#[derive(Deserialize)]
+struct RawDefinition<'a> {
+    #[serde(borrow, default)]
+    remove: Vec<&'a RawValue>,
+    #[serde(borrow, default)]
+    codes: Vec<RawEntry<'a>>,
+}
+
+#[derive(Deserialize)]
+struct RawEntry<'a> {
+    #[serde(borrow)]
+    letters: &'a RawValue,
+    #[serde(borrow)]
+    code: &'a RawValue,
+    #[serde(borrow, default)]
+    aliases: Vec<&'a RawValue>,
+}
+
+impl Offsets {
+    /// Finds the offsets of the values in the valid definition, the offsets are empty if the definition is not valid.
+    fn of(text: &str, format: Format) -> Offsets {
+        match format {
+            Format::Toml => toml::from_str::<SpannedDefinition>(text)
+                .map(|definition| {
+                    let offset = |value: &Spanned<String>| value.span().start + 1;
+                    Offsets {
+                        remove: definition.remove.iter().map(offset).collect(),
+                        codes: definition.codes.iter()
+                            .map(|entry| EntryOffsets {
+                                letters: offset(&entry.letters),
+                                code: offset(&entry.code),
+                                aliases: entry.aliases.iter().map(offset).collect(),
+                            })
+                            .collect(),
+                    }
+                })
+                .unwrap_or_default(),
+            _ => serde_json::from_str::<RawDefinition>(text)
+                .map(|definition| {
+                    let offset = |value: &&RawValue| value.get().as_ptr() as usize - text.as_ptr() as usize + 1;
+                    Offsets {
+                        remove: definition.remove.iter().map(offset).collect(),
+                        codes: definition.codes.iter()
+                            .map(|entry| EntryOffsets {
+                                letters: offset(&entry.letters),
+                                code: offset(&entry.code),
+                                aliases: entry.aliases.iter().map(offset).collect(),
+                            })
+                            .collect(),
+                    }
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Builds the codes of the entries.
+/// In the strict mode, the invalid entries and the letters defined twice are reported as the diagnostics at the values,
+/// otherwise, the first invalid entry fails with [`Error::InvalidCode`].
+fn entries(definition: &Definition, content: &[u8], offsets: &Offsets, strict: bool) -> Result<(Vec<Code>, Vec<Diagnostic>), Error> {
+    if !strict {
+        let entries = definition.codes.iter()
+            .map(|entry| Code::try_new(&entry.letters, &entry.code)?.try_with_aliases(&entry.aliases))
+            .collect::<Result<Vec<_>, Error>>()?;
+        return Ok((entries, Vec::new()));
+    }
+    let mut codes = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut defined = HashMap::new(); // letters -> the line number defining them.
+    for (i, entry) in definition.codes.iter().enumerate() {
+        let offsets = offsets.codes.get(i);
+        let mut locate = |offset: Option<usize>, text: &str, e: Error| {
+            let (line, column) = position_of(content, offset.unwrap_or_default());
+            let message = match e {
+                Error::InvalidCode(message) => message,
+                e => e.to_string(),
+            };
+            diagnostics.push(Diagnostic::new(line, column, text, format!("a valid code ({message})")));
+        };
+        let (line, column) = position_of(content, offsets.map(|o| o.letters).unwrap_or_default());
+        if let Err(e) = alphabet::validate_letters(&entry.letters) {
+            locate(offsets.map(|o| o.letters), &entry.letters, e);
+        } else if let Err(e) = alphabet::validate_spelling(&entry.code) {
+            locate(offsets.map(|o| o.code), &entry.code, e);
+        } else if let Some((j, alias, e)) = entry.aliases.iter()
+            .enumerate()
+            .find_map(|(j, alias)| alphabet::validate_spelling(alias).err().map(|e| (j, alias, e))) {
+            locate(offsets.and_then(|o| o.aliases.get(j).copied()), alias, e);
+        } else if let Some(previous) = defined.insert(entry.letters.as_str(), line) {
+            let expected = format!("unique letters, which are already defined in line {previous}");
+            diagnostics.push(Diagnostic::new(line, column, entry.letters.as_str(), expected));
+        } else {
+            codes.push(Code::new(&entry.letters, &entry.code).with_aliases(&entry.aliases));
+        }
+    }
+    Ok((codes, diagnostics))
+}
+
+/// Builds the codes from the definition in the structured format.
+/// The letters of the codes in the definition must be unique.
+/// The bases, the included files, and the removal of the letters are applied in order, and the codes of the definition override them.
+/// The syntax error is reported as [`Error::Diagnostics`] in the strict mode, and as [`Error::Parse`] otherwise.
+/// The invalid entries and the letters defined twice are reported as [`Error::Diagnostics`] in the strict mode, and as [`Error::InvalidCode`] otherwise.
+/// The removal of the undefined letters is reported as [`Error::Diagnostics`] in the strict mode, and skipped with a warning otherwise.
+pub(crate) fn build(content: &[u8], format: Format, strict: bool, context: &Context) -> Result<Codes, Error> {
+    let definition = deserialize(content, format)
+        .map_err(|diagnostic| if strict {
+            Error::Diagnostics(vec![diagnostic])
+        } else {
+            Error::Parse(diagnostic.to_string())
+        })?;
+    let offsets = if strict || !definition.remove.is_empty() {
+        Offsets::of(&String::from_utf8_lossy(content), format)
+    } else {
+        Offsets::default()
+    };
+    let (entries, mut diagnostics) = entries(&definition, content, &offsets, strict)?;
+    let inherits = !definition.base.names().is_empty() || !definition.include.is_empty();
+    if diagnostics.is_empty() && (!inherits || !entries.is_empty()) {
+        alphabet::validate_unique_letters(&entries)?;
+    }
+    let mut inherited = Vec::new();
+    for base in definition.base.names() {
+        inherited.extend(context.base(base, strict).map_err(base_error)?);
+    }
+    for target in &definition.include {
+        inherited.extend(context.include(target, strict)?);
+    }
+    for (i, letters) in definition.remove.iter().enumerate() {
+        if !remove_letters(&mut inherited, letters) {
+            let (line, column) = position_of(content, offsets.remove.get(i).copied().unwrap_or_default());
+            diagnostics.push(Diagnostic::new(line, column, letters.as_str(), "the letters defined in the bases and the included files"));
+        }
+    }
+    if strict && !diagnostics.is_empty() {
+        return Err(Error::Diagnostics(diagnostics));
+    }
+    for diagnostic in &diagnostics {
+        log::warn!("Skipped the invalid removal {diagnostic}");
+    }
+    let mut codes = inherited.into_iter().collect::<Codes>();
+    codes.extend(entries.into_iter().collect());
+    Ok(codes.with_metadata(definition.metadata.into()))
+}
+
+/// Serializes the codes into the definition in the structured format, which builds the same codes by [`build`].
+pub(crate) fn serialize(codes: &Codes, format: Format) -> Result<String, Error> {
+    let definition = Definition {
+        base: Bases::None,
+        include: Vec::new(),
+        remove: Vec::new(),
+        metadata: codes.metadata().into(),
+        codes: codes.entries()
+            .map(|code| Entry {
+                letters: code.letters().to_string(),
+                code: code.code().to_string(),
+                aliases: code.aliases().map(String::from).collect(),
+                _note: None,
+            })
+            .collect(),
+    };
+    match format {
+        Format::Toml => toml::to_string(&definition)
+            .map_err(|e| Error::Parse(format!("failed to serialize into TOML: {e}"))),
+        _ => serde_json::to_string_pretty(&definition)
+            .map(|json| json + "\n")
+            .map_err(|e| Error::Parse(format!("failed to serialize into JSON: {e}"))),
+    }
+}
+
+/// Returns the metadata of the definition without building the codes.
+pub(crate) fn metadata(content: &[u8], format: Format) -> Metadata {
+    match deserialize(content, format) {
+        Ok(definition) => definition.metadata.into(),
+        Err(e) => {
+            log::warn!("Invalid {format} definition: {e}");
+            Metadata::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+base = "nato"
+
+[metadata]
+display-name = "Fruits"
+language = "en"
+year = 2024
+
+[[codes]]
+letters = "A"
+code = "Apple"
+aliases = ["Apricot"]
+note = "the most famous fruit"
+
+[[codes]]
+letters = "Ch"
+code = "Cherry"
+"#;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Format::detect(Some(Path::new("fruits.toml")), b""), Format::Toml);
+        assert_eq!(Format::detect(Some(Path::new("fruits.JSON")), b""), Format::Json);
+        assert_eq!(Format::detect(None, TOML.as_bytes()), Format::Toml);
+        assert_eq!(Format::detect(None, b"# fruits\n{ \"codes\": [] }"), Format::Json);
+        assert_eq!(Format::detect(None, b"#@ language: en\nA = Apple\nB, Banana"), Format::Lines);
+        assert_eq!(Format::detect(None, b"A = \"Apple\""), Format::Lines);
+    }
+
+    #[test]
+    fn test_build_toml() {
+        let codes = build(TOML.as_bytes(), Format::Toml, true, &Context::default()).unwrap();
+        assert_eq!(codes.lookup("A").map(|c| c.code()), Some("Apple"));
+        assert_eq!(codes.lookup("A").map(|c| c.aliases().collect::<Vec<_>>()), Some(vec!["Apricot"]));
+        assert_eq!(codes.lookup("Ch").map(|c| c.code()), Some("Cherry"));
+        assert_eq!(codes.lookup("B").map(|c| c.code()), Some("Bravo"));
+        assert_eq!(codes.metadata().year(), Some(2024));
+        assert_eq!(metadata(TOML.as_bytes(), Format::Toml).display_name(), Some("Fruits"));
+    }
+
+    #[test]
+    fn test_build_json() {
+        let json = r#"{ "metadata": { "language": "en" }, "codes": [ { "letters": "A", "code": "Apple" } ] }"#;
+        let codes = build(json.as_bytes(), Format::Json, true, &Context::default()).unwrap();
+        assert_eq!(codes.entries().count(), 1);
+        assert_eq!(codes.metadata().language(), Some("en"));
+        assert_eq!(serialize(&codes, Format::Json).unwrap(), format!("{}\n", r#"{
+  "metadata": {
+    "language": "en"
+  },
+  "codes": [
+    {
+      "letters": "A",
+      "code": "Apple"
+    }
+  ]
+}"#));
+
+        let Err(Error::Diagnostics(diagnostics)) = build(b"{\n  \"codes\": [ { \"letters\": \"A\" } ]\n}", Format::Json, true, &Context::default()) else {
+            panic!("the missing code should be reported");
+        };
+        assert_eq!((diagnostics[0].line, diagnostics[0].expected.as_str()), (2, "a valid JSON definition (missing field `code`)"));
+        let duplicated = r#"{ "codes": [ { "letters": "A", "code": "Apple" }, { "letters": "A", "code": "Avocado" } ] }"#;
+        assert!(matches!(build(duplicated.as_bytes(), Format::Json, false, &Context::default()), Err(Error::InvalidCode(_))));
+        let invalid = "{\n  \"codes\": [\n    { \"letters\": \"A\", \"code\": \"Apple\" },\n    { \"letters\": \"A\", \"code\": \"Avocado\" },\n    { \"letters\": \"B\", \"code\": \"Banana\", \"aliases\": [\"Berry\", \" \"] }\n  ]\n}";
+        let Err(Error::Diagnostics(diagnostics)) = build(invalid.as_bytes(), Format::Json, true, &Context::default()) else {
+            panic!("the invalid entries should be reported");
+        };
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column, d.text.as_str())).collect::<Vec<_>>(), vec![(4, 19, "A"), (5, 63, " ")]);
+        assert_eq!(diagnostics[0].expected, "unique letters, which are already defined in line 3");
+    }
+
+    #[test]
+    fn test_toml_diagnostics() {
+        let Err(Error::Diagnostics(diagnostics)) = build(b"[[codes]]\nletters = \"A\"\ncode = \"Apple\"\nnotes = \"typo\"\n", Format::Toml, true, &Context::default()) else {
+            panic!("the unknown field should be reported");
+        };
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (4, 1));
+        assert!(matches!(build(b"base = \"unknown\"", Format::Toml, false, &Context::default()), Err(Error::Parse(_))));
+        let invalid = "[[codes]]\nletters = \"A B\"\ncode = \"Apple\"\n\n[[codes]]\nletters = \"C\"\ncode = \"Cherry\"\n\n[[codes]]\nletters = \"C\"\ncode = \"Coconut\"\n";
+        let Err(Error::Diagnostics(diagnostics)) = build(invalid.as_bytes(), Format::Toml, true, &Context::default()) else {
+            panic!("the invalid entries should be reported");
+        };
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column, d.text.as_str())).collect::<Vec<_>>(), vec![(2, 12, "A B"), (10, 12, "C")]);
+        assert_eq!(diagnostics[1].expected, "unique letters, which are already defined in line 6");
+        assert!(matches!(build(invalid.as_bytes(), Format::Toml, false, &Context::default()), Err(Error::InvalidCode(_))));
+
+        let removal = "base = \"nato\"\nremove = [\"A\", \"Zz\", 'B', \"\\u0416\"]\n";
+        let Err(Error::Diagnostics(diagnostics)) = build(removal.as_bytes(), Format::Toml, true, &Context::default()) else {
+            panic!("the undefined letters should be reported");
+        };
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column, d.text.as_str())).collect::<Vec<_>>(), vec![(2, 17, "Zz"), (2, 28, "Ж")]);
+        let commented = "# remove \"Zz\" after the base\nbase = \"nato\"\nremove = [\"Zz\"]\n";
+        let Err(Error::Diagnostics(diagnostics)) = build(commented.as_bytes(), Format::Toml, true, &Context::default()) else {
+            panic!("the undefined letters should be reported");
+        };
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 12));
+        let codes = build(removal.as_bytes(), Format::Toml, false, &Context::default()).unwrap();
+        assert!(codes.lookup("A").is_none() && codes.lookup("B").is_none());
+        let codes = build(b"base = \"nato\"\nremove = [\"a\", \"b\"]\n", Format::Toml, true, &Context::default()).unwrap();
+        assert!(codes.lookup("A").is_none() && codes.lookup("B").is_none());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{registry, Codes, Error, Matching, PhoneticCode, Registry};
+
+/// Represents a candidate of the phonetic code detected by [`detect`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+    name: String,
+    recognized: usize,
+    total: usize,
+    entries: usize,
+}
+
+impl Detection {
+    /// The minimum confidence of the detected phonetic code, the candidates below this are not detected (see [`Detection::is_confident`]).
+    pub const MIN_CONFIDENCE: f64 = 0.5;
+
+    /// Returns the name of the phonetic code.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of the tokens recognized by the phonetic code.
+    pub fn recognized(&self) -> usize {
+        self.recognized
+    }
+
+    /// Returns the number of the tokens in the input, except the blank tokens and the separator words.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the ratio of the recognized tokens, from 0.0 to 1.0.
+    pub fn confidence(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.recognized as f64 / self.total as f64
+        }
+    }
+
+    /// Returns `true` if the confidence is at least [`Detection::MIN_CONFIDENCE`], and some tokens are recognized.
+    pub fn is_confident(&self) -> bool {
+        self.recognized > 0 && self.confidence() >= Self::MIN_CONFIDENCE
+    }
+
+    /// Builds the detected phonetic code from the default registry (see [`registry`](crate::registry)).
+    /// Use [`Registry::codes`] with [`Detection::name`] for the phonetic codes detected by [`Registry::detect`].
+    pub fn codes(&self) -> Result<Codes, Error> {
+        registry().codes(&self.name).cloned()
+    }
+}
+
+impl std::fmt::Display for Detection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (confidence: {:.2}, {}/{} tokens)", self.name, self.confidence(), self.recognized, self.total)
+    }
+}
+
+/// Returns the best candidate of the ranking by [`detect`], or [`Error::Undetected`] with the top candidates if the best one is not confident
+/// (see [`Detection::is_confident`]).
+///
+/// ```rust
+/// use spellout::Matching;
+///
+/// let ranking = spellout::detect("Able Baker Charlie", Matching::Exact);
+/// assert_eq!(spellout::best_detection(ranking).unwrap().name(), "uk");
+/// assert!(spellout::best_detection(spellout::detect("xyzzy plugh", Matching::Exact)).is_err());
+/// ```
+pub fn best_detection(ranking: Vec<Detection>) -> Result<Detection, Error> {
+    match ranking.first() {
+        Some(best) if best.is_confident() => Ok(best.clone()),
+        _ => Err(Error::Undetected(ranking.into_iter().take(MAX_CANDIDATES).collect())),
+    }
+}
+
+/// The maximum number of the candidates in [`Error::Undetected`].
+const MAX_CANDIDATES: usize = 5;
+
+/// Detects the phonetic code of the given spelled text, by scoring the tokens against all the alphabets in the default registry
+/// (see [`registry`](crate::registry)).
+///
+/// The result is ranked in descending order of the confidence.
+/// The ties are broken by the smaller number of entries (the more specific vocabulary), then, the default phonetic code (NATO) comes first.
+///
+/// ```rust
+/// use spellout::Matching;
+///
+/// let ranking = spellout::detect("Kaufmann Anton Ärger Berta", Matching::Exact);
+/// assert_eq!(ranking[0].name(), "din5009_1983");
+/// assert_eq!(ranking[0].confidence(), 1.0);
+/// ```
+pub fn detect(text: &str, matching: Matching) -> Vec<Detection> {
+    registry().detect(text, matching)
+}
+
+pub(crate) fn detect_in(registry: &Registry, text: &str, matching: Matching) -> Vec<Detection> {
+    let mut result = registry.names()
+        .filter_map(|name| registry.codes(name).ok().map(|codes| score(name.to_string(), codes, text, matching)))
+        .collect::<Vec<_>>();
+    let default_name = PhoneticCode::Nato.to_string();
+    result.sort_by(|a, b| {
+        b.confidence().total_cmp(&a.confidence())
+            .then(a.entries.cmp(&b.entries))
+            .then((b.name == default_name).cmp(&(a.name == default_name)))
+            .then(a.name.cmp(&b.name))
+    });
+    result
+}
+
+fn score(name: String, codes: &Codes, text: &str, matching: Matching) -> Detection {
+    let mut recognized = 0;
+    let mut total = 0;
+    for token in codes.tokenize(text, matching) {
+        if codes.find_separator(&token, matching).is_some() {
+            continue; // separator words are common to all of phonetic codes.
+        }
+        total += 1;
+        if codes.decode_item(&token, matching).is_some() {
+            recognized += 1;
+        }
+    }
+    Detection { name, recognized, total, entries: codes.entries().count() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let ranking = detect("Hotel Echo Lima Lima Oscar", Matching::Exact);
+        assert_eq!(ranking[0].name(), "nato");
+        assert_eq!(ranking[0].recognized(), 5);
+
+        let ranking = detect("Able Baker Charlie", Matching::Exact);
+        assert_eq!(ranking[0].name(), "uk");
+
+        let ranking = detect("Anna Äsch Berta", Matching::Exact);
+        assert_eq!(ranking[0].name(), "switzerland");
+
+        let ranking = detect("Dixie Echo", Matching::Exact);
+        assert_eq!(ranking[0].name(), "usaairpots");
+        assert_eq!(ranking[0].confidence(), 1.0);
+        assert!(ranking[0].is_confident());
+    }
+
+    #[test]
+    fn test_undetected() {
+        let ranking = detect("xyzzy plugh", Matching::Exact);
+        assert!(!ranking[0].is_confident());
+        match best_detection(ranking) {
+            Err(Error::Undetected(candidates)) => assert_eq!(candidates.len(), MAX_CANDIDATES),
+            other => panic!("unexpected: {other:?}"),
+        }
+        let ranking = detect("Hotel Xyzzy Plugh", Matching::Exact);
+        assert!(ranking[0].confidence() > 0.0);
+        assert!(best_detection(ranking).is_err());
+        assert!(matches!(best_detection(Vec::new()), Err(Error::Undetected(candidates)) if candidates.is_empty()));
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+/// Represents a problem found in parsing a code file, see [`CodesBuilder::build_from_file_strict`](crate::CodesBuilder::build_from_file_strict).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The line number of the problem (1-origin).
+    pub line: usize,
+    /// The column number of the problem in characters (1-origin).
+    pub column: usize,
+    /// The offending text, which is empty if something is missing.
+    pub text: String,
+    /// The description of what was expected.
+    pub expected: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn new<T: Into<String>, E: Into<String>>(line: usize, column: usize, text: T, expected: E) -> Self {
+        Diagnostic { line, column, text: text.into(), expected: expected.into() }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "{}:{}: expected {}", self.line, self.column, self.expected)
+        } else {
+            write!(f, "{}:{}: expected {}, found '{}'", self.line, self.column, self.expected, self.text)
+        }
+    }
+}
+
+/// Returns the column (1-origin, in characters) of the given piece in the line, the piece must be a sub-slice of the line.
+pub(crate) fn column_of(line: &str, piece: &str) -> usize {
+    let offset = (piece.as_ptr() as usize).saturating_sub(line.as_ptr() as usize).min(line.len());
+    line.get(..offset).map(|head| head.chars().count()).unwrap_or_default() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "  Ä, Ärger";
+        let code = line.split_once(", ").map(|(_, code)| code).unwrap();
+        assert_eq!(column_of(line, code), 6);
+        assert_eq!(column_of(line, line.trim()), 3);
+        let diagnostic = Diagnostic::new(2, 6, "", "a code after the delimiter");
+        assert_eq!(diagnostic.to_string(), "2:6: expected a code after the delimiter");
+    }
+}
@@ -0,0 +1,160 @@
+use clap::ValueEnum;
+
+use crate::definition::{self, Format};
+use crate::{Code, Codes, Error, Metadata};
+
+/// The formats to export the phonetic codes (see [`Codes::export`](crate::Codes::export)).
+///
+/// The line format, TOML, and JSON are the definition formats, which [`CodesBuilder::build_from_file`](crate::CodesBuilder::build_from_file)
+/// builds the same codes from.
+/// CSV and Markdown are the tables for the other tools and the documents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, ValueEnum)]
+pub enum ExportFormat {
+    /// The line format, such as "A, Alfa", with the `#@` headers.
+    #[default]
+    Lines,
+    Toml,
+    Json,
+    /// The comma separated values with the header row of `letters,code,aliases`.
+    Csv,
+    /// The table in Markdown.
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Returns the file extension of this format, such as "toml".
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Lines => "txt",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+pub(crate) fn export(codes: &Codes, format: ExportFormat) -> Result<String, Error> {
+    match format {
+        ExportFormat::Lines => export_lines(codes),
+        ExportFormat::Toml => definition::serialize(codes, Format::Toml),
+        ExportFormat::Json => definition::serialize(codes, Format::Json),
+        ExportFormat::Csv => Ok(export_csv(codes)),
+        ExportFormat::Markdown => Ok(export_markdown(codes)),
+    }
+}
+
+fn export_lines(codes: &Codes) -> Result<String, Error> {
+    let mut result = headers(codes.metadata());
+    for code in codes.entries() {
+        if let Some(spelling) = code.spellings().find(|s| !is_representable(s)) {
+            return Err(Error::InvalidCode(format!("{spelling}: the spelling is not representable in the line format")));
+        }
+        let spellings = code.spellings().collect::<Vec<_>>().join(" | ");
+        result.push_str(&format!("{}, {spellings}\n", escape_letters(code.letters())));
+    }
+    Ok(result)
+}
+
+fn headers(metadata: &Metadata) -> String {
+    [
+        ("display-name", metadata.display_name().map(String::from)),
+        ("language", metadata.language().map(String::from)),
+        ("region", metadata.region().map(String::from)),
+        ("standard", metadata.standard().map(String::from)),
+        ("year", metadata.year().map(|year| year.to_string())),
+        ("source", metadata.source().map(String::from)),
+    ].into_iter()
+        .filter_map(|(key, value)| value.map(|value| format!("#@ {key}: {value}\n")))
+        .collect()
+}
+
+/// Returns `true` if the spelling is read back as it is in the line format,
+/// where '#' starts the comment, '|' separates the aliases, and the surrounding whitespaces are trimmed.
+fn is_representable(spelling: &str) -> bool {
+    !spelling.is_empty() && spelling.trim() == spelling
+        && !spelling.contains(['#', '|', '\n', '\r'])
+}
+
+/// Escapes the letters into `\u{...}` if any letter would be read as the delimiter, the comment, or the other syntax in the line format.
+fn escape_letters(letters: &str) -> String {
+    let special = |c: char| c.is_whitespace() || c.is_control() || ",;:=#|\\[{".contains(c);
+    if letters.chars().any(special) {
+        letters.chars().map(|c| format!("\\u{{{:04X}}}", c as u32)).collect()
+    } else {
+        letters.to_string()
+    }
+}
+
+fn export_csv(codes: &Codes) -> String {
+    let mut result = String::from("letters,code,aliases\n");
+    for code in codes.entries() {
+        let row = [code.letters().to_string(), code.code().to_string(), aliases(code)]
+            .map(|cell| quote_csv(&cell))
+            .join(",");
+        result.push_str(&row);
+        result.push('\n');
+    }
+    result
+}
+
+/// Quotes the cell of CSV in RFC 4180, if the cell has the comma, the double quote, or the line break.
+/// This is the quoting of [`ExportFormat::Csv`], for writing the other tables in the same manner.
+///
+/// ```
+/// assert_eq!(spellout::quote_csv("Alfa"), "Alfa");
+/// assert_eq!(spellout::quote_csv("\"Comma\", Point"), "\"\"\"Comma\"\", Point\"");
+/// ```
+pub fn quote_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn export_markdown(codes: &Codes) -> String {
+    let mut result = String::from("| Letters | Code | Aliases |\n| --- | --- | --- |\n");
+    for code in codes.entries() {
+        let row = [code.letters().to_string(), code.code().to_string(), aliases(code)]
+            .map(|cell| cell.replace('\\', "\\\\").replace('|', "\\|"))
+            .join(" | ");
+        result.push_str(&format!("| {row} |\n"));
+    }
+    result
+}
+
+fn aliases(code: &Code) -> String {
+    code.aliases().collect::<Vec<_>>().join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{self, Context};
+
+    #[test]
+    fn test_round_trip_all_alphabets() {
+        for name in crate::registry().names() {
+            let original = crate::registry().codes(name).unwrap();
+            for (format, syntax) in [(ExportFormat::Lines, Format::Lines), (ExportFormat::Toml, Format::Toml), (ExportFormat::Json, Format::Json)] {
+                let exported = original.export(format).unwrap();
+                let built = codes::build(exported.as_bytes(), syntax, true, &Context::default())
+                    .unwrap_or_else(|e| panic!("{name} in {format:?}: {e}"));
+                assert_eq!(&built, original, "{name} in {format:?}");
+                // the name of the metadata is given by the file name in building.
+                assert_eq!(headers(built.metadata()), headers(original.metadata()), "{name} in {format:?}");
+                assert_eq!(Format::detect(None, exported.as_bytes()), syntax, "{name} in {format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_tables() {
+        let codes = codes::build_from_reader("A, Alfa | Alpha\n\\u{2C}, \"Comma\", Point".as_bytes()).unwrap();
+        assert_eq!(codes.export(ExportFormat::Lines).unwrap(), "\\u{002C}, \"Comma\", Point\nA, Alfa | Alpha\n");
+        assert_eq!(codes.export(ExportFormat::Csv).unwrap(), "letters,code,aliases\n\",\",\"\"\"Comma\"\", Point\",\nA,Alfa,Alpha\n");
+        assert_eq!(codes.export(ExportFormat::Markdown).unwrap(),
+            "| Letters | Code | Aliases |\n| --- | --- | --- |\n| , | \"Comma\", Point |  |\n| A | Alfa | Alpha |\n");
+    }
+}
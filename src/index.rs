@@ -0,0 +1,140 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::matching::normalize;
+use crate::{tokenize, CasePolicy, Code};
+
+/// The letters of the code, which identify the code in the sorted entries of [`Codes`](crate::Codes).
+type Letters = Cow<'static, str>;
+
+/// Indexes of [`Codes`](crate::Codes) for looking up the codes by the letters and by the spellings.
+/// Each index maps a key to the letters of the codes having the key, in the sorted order,
+/// and the first code wins if the keys conflict.
+/// The index is updated by [`Index::insert`] and [`Index::remove`] without rebuilding, since the keys do not depend on the positions of the codes.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Index {
+    /// letters folded by the case policy -> codes.
+    folded: HashMap<String, Vec<Letters>>,
+    /// spellings (including aliases) -> codes.
+    spellings: HashMap<String, Vec<Letters>>,
+    /// lower case spellings -> codes.
+    lower: HashMap<String, Vec<Letters>>,
+    /// upper case spellings -> codes.
+    upper: HashMap<String, Vec<Letters>>,
+    /// spellings normalized for the tolerant matching -> codes.
+    tolerant: HashMap<String, Vec<Letters>>,
+    /// The upper bounds of the lengths, which are not shrunk by the removal.
+    max_unit_len: usize,
+    max_pieces: usize,
+    max_spelling_len: usize,
+}
+
+/// The case form of the spelling found in the index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Form {
+    Lower,
+    Upper,
+}
+
+impl Form {
+    pub(crate) fn apply(&self, s: &str) -> String {
+        match self {
+            Form::Lower => s.to_lowercase(),
+            Form::Upper => s.to_uppercase(),
+        }
+    }
+}
+
+impl Index {
+    pub(crate) fn build(codes: &[Code], case: CasePolicy) -> Index {
+        let mut index = Index { max_unit_len: 1, max_pieces: 1, ..Default::default() };
+        for code in codes {
+            index.insert(code, case);
+        }
+        index
+    }
+
+    /// Adds the keys of the given code.
+    pub(crate) fn insert(&mut self, code: &Code, case: CasePolicy) {
+        let letters = &code.letters;
+        add(&mut self.folded, case.fold(letters), letters);
+        self.max_unit_len = self.max_unit_len.max(code.len());
+        for spelling in code.spellings() {
+            add(&mut self.spellings, spelling.to_string(), letters);
+            add(&mut self.lower, spelling.to_lowercase(), letters);
+            add(&mut self.upper, spelling.to_uppercase(), letters);
+            add(&mut self.tolerant, normalize(case, spelling), letters);
+            self.max_pieces = self.max_pieces.max(tokenize::count_pieces(spelling));
+            self.max_spelling_len = self.max_spelling_len.max(spelling.chars().count());
+        }
+    }
+
+    /// Removes the keys of the given code, the other codes having the same keys take them over.
+    pub(crate) fn remove(&mut self, code: &Code, case: CasePolicy) {
+        let letters = code.letters();
+        discard(&mut self.folded, &case.fold(letters), letters);
+        for spelling in code.spellings() {
+            discard(&mut self.spellings, spelling, letters);
+            discard(&mut self.lower, &spelling.to_lowercase(), letters);
+            discard(&mut self.upper, &spelling.to_uppercase(), letters);
+            discard(&mut self.tolerant, &normalize(case, spelling), letters);
+        }
+    }
+
+    /// Finds the letters of the code by the letters folded by the case policy.
+    pub(crate) fn find_folded(&self, letters: &str, case: CasePolicy) -> Option<&str> {
+        first(&self.folded, &case.fold(letters))
+    }
+
+    /// Finds the letters of the code by the identical spelling.
+    pub(crate) fn find_spelling(&self, item: &str) -> Option<&str> {
+        first(&self.spellings, item)
+    }
+
+    /// Finds the letters of the code by the lower case or upper case form of the spelling, and returns the form.
+    pub(crate) fn find_case_form(&self, item: &str) -> Option<(&str, Form)> {
+        first(&self.lower, item).map(|letters| (letters, Form::Lower))
+            .or_else(|| first(&self.upper, item).map(|letters| (letters, Form::Upper)))
+    }
+
+    /// Finds the letters of the code by the spelling normalized for the tolerant matching.
+    pub(crate) fn find_tolerant(&self, item: &str, case: CasePolicy) -> Option<&str> {
+        first(&self.tolerant, &normalize(case, item))
+    }
+
+    /// Returns the number of characters in the longest spelling unit.
+    pub(crate) fn max_unit_len(&self) -> usize {
+        self.max_unit_len
+    }
+
+    /// Returns the number of pieces in the longest spelling.
+    pub(crate) fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Returns the number of characters in the longest spelling.
+    pub(crate) fn max_spelling_len(&self) -> usize {
+        self.max_spelling_len
+    }
+}
+
+/// Adds the letters to the codes of the key, in the sorted order.
+fn add(map: &mut HashMap<String, Vec<Letters>>, key: String, letters: &Letters) {
+    let codes = map.entry(key).or_default();
+    if let Err(i) = codes.binary_search(letters) {
+        codes.insert(i, letters.clone());
+    }
+}
+
+fn discard(map: &mut HashMap<String, Vec<Letters>>, key: &str, letters: &str) {
+    if let Some(codes) = map.get_mut(key) {
+        codes.retain(|l| l != letters);
+        if codes.is_empty() {
+            map.remove(key);
+        }
+    }
+}
+
+fn first<'a>(map: &'a HashMap<String, Vec<Letters>>, key: &str) -> Option<&'a str> {
+    map.get(key).and_then(|codes| codes.first()).map(|letters| letters.as_ref())
+}
@@ -0,0 +1,167 @@
+use crate::{Code, Codes, Matching};
+
+/// The layout for spelling the words in a single line, such as "Hotel Echo / Whiskey Oscar",
+/// see [`Codes::spell_inline`](crate::Codes::spell_inline) and [`Codes::tokenize_inline`](crate::Codes::tokenize_inline).
+///
+/// The default layout delimits the codes with a space, the words with " / ", and marks the unknown characters with "?".
+/// The codes are spelled in the canonical spellings unless [`InlineOptions::preserve_case`] is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineOptions {
+    letter_separator: String,
+    word_separator: String,
+    unknown: String,
+    preserve_case: bool,
+}
+
+impl Default for InlineOptions {
+    fn default() -> Self {
+        InlineOptions {
+            letter_separator: " ".to_string(),
+            word_separator: " / ".to_string(),
+            unknown: "?".to_string(),
+            preserve_case: false,
+        }
+    }
+}
+
+impl InlineOptions {
+    /// Sets the separator between the codes in a word.
+    pub fn letter_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.letter_separator = separator.into();
+        self
+    }
+
+    /// Sets the separator between the words.
+    pub fn word_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.word_separator = separator.into();
+        self
+    }
+
+    /// Sets the marker for the characters which have no phonetic code.
+    pub fn unknown<S: Into<String>>(mut self, marker: S) -> Self {
+        self.unknown = marker.into();
+        self
+    }
+
+    /// Spells the codes in the case of the letters, such as "hotel" for 'h' (see [`Code::spell`](crate::Code::spell)).
+    /// The case-preserving spellings are decoded into the original case in [`Matching::Tolerant`] mode.
+    pub fn preserve_case(mut self, enabled: bool) -> Self {
+        self.preserve_case = enabled;
+        self
+    }
+}
+
+pub(crate) fn spell(codes: &Codes, words: &str, options: &InlineOptions) -> String {
+    let mut result: Vec<String> = Vec::new();
+    let mut word: Vec<String> = Vec::new();
+    for (unit, code) in codes.encode(words) {
+        if unit.trim().is_empty() {
+            if !word.is_empty() {
+                result.push(std::mem::take(&mut word).join(&options.letter_separator));
+            }
+        } else {
+            let spelled = match code {
+                Some(code) if options.preserve_case => code.spell(&unit),
+                Some(code) => code.code().to_string(),
+                None => options.unknown.clone(),
+            };
+            word.push(spelled);
+        }
+    }
+    if !word.is_empty() {
+        result.push(word.join(&options.letter_separator));
+    }
+    result.join(&options.word_separator)
+}
+
+pub(crate) fn tokenize(codes: &Codes, text: &str, options: &InlineOptions, matching: Matching) -> Vec<String> {
+    let mut items = Vec::new();
+    let words = split(text, &options.word_separator).into_iter()
+        .filter(|word| !word.trim().is_empty());
+    let separator = options.letter_separator.trim();
+    let max_pieces = codes.entries()
+        .chain(codes.separators().entries())
+        .flat_map(Code::spellings)
+        .map(|spelling| spelling.matches(separator).count() + 1)
+        .max()
+        .unwrap_or(1);
+    for (i, word) in words.enumerate() {
+        if i > 0 {
+            items.push(String::new()); // the blank token is a word break.
+        }
+        if separator.is_empty() {
+            items.extend(codes.tokenize(word, matching));
+        } else {
+            items.extend(join_codes(codes, split(word, separator), separator, max_pieces, matching));
+        }
+    }
+    items
+}
+
+/// Joins the longest runs of the pieces which match the known spellings, such as "X" and "ray" into "X-ray" split by the separator "-".
+/// The runs are at most `max_pieces` long, which is the number of the pieces in the longest spelling split by the separator.
+fn join_codes(codes: &Codes, pieces: Vec<&str>, separator: &str, max_pieces: usize, matching: Matching) -> Vec<String> {
+    let pieces = pieces.into_iter()
+        .map(str::trim)
+        .filter(|piece| !piece.is_empty())
+        .collect::<Vec<_>>();
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < pieces.len() {
+        let (len, item) = (2..=max_pieces.min(pieces.len() - i)).rev()
+            .map(|len| (len, pieces[i..i + len].join(separator)))
+            .find(|(_, candidate)| codes.decode_item(candidate, matching).is_some())
+            .unwrap_or_else(|| (1, pieces[i].to_string()));
+        items.push(item);
+        i += len;
+    }
+    items
+}
+
+/// Splits the text by the separator, the surrounding whitespaces of the separator are optional unless it consists of only whitespaces.
+fn split<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let trimmed = separator.trim();
+    let separator = if trimmed.is_empty() { separator } else { trimmed };
+    if separator.is_empty() {
+        vec![text]
+    } else {
+        text.split(separator).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodesBuilder, DecodeOptions, PhoneticCode};
+
+    #[test]
+    fn test_round_trip() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let options = InlineOptions::default();
+        let spelled = spell(&nato, "HELLO  WORLD", &options);
+        assert_eq!(spelled, "Hotel Echo Lima Lima Oscar / Whiskey Oscar Romeo Lima Delta");
+        assert_eq!(nato.decode(tokenize(&nato, &spelled, &options, Matching::Exact)), "HELLO WORLD");
+        assert_eq!(nato.decode(tokenize(&nato, "Hotel Echo/Whiskey", &options, Matching::Exact)), "HE W");
+
+        let options = InlineOptions::default().letter_separator(", ").word_separator(" | ").unknown("<?>");
+        let spelled = spell(&nato, "X€ yo", &options);
+        assert_eq!(spelled, "X-ray, <?> | Yankee, Oscar");
+        let report = nato.decode_report(tokenize(&nato, &spelled, &options, Matching::Exact), DecodeOptions::default()).unwrap();
+        assert_eq!(report.text(), "X  YO");
+        assert_eq!(report.unknowns()[0].text, "<?>");
+
+        let options = options.preserve_case(true);
+        let spelled = spell(&nato, "X€ yo", &options);
+        assert_eq!(spelled, "X-ray, <?> | yankee, oscar");
+        assert_eq!(nato.decode_with(tokenize(&nato, &spelled, &options, Matching::Tolerant), Matching::Tolerant), "X  yo");
+
+        let options = InlineOptions::default().letter_separator("-");
+        assert_eq!(spell(&nato, "AX", &options), "Alpha-X-ray");
+        assert_eq!(tokenize(&nato, "Alpha-X-ray", &options, Matching::Exact), vec!["Alpha", "X-ray"]);
+
+        let long = vec!["Hotel"; 5000].join("-");
+        let start = std::time::Instant::now();
+        assert_eq!(tokenize(&nato, &long, &options, Matching::Exact).len(), 5000);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5), "took {:?}", start.elapsed());
+    }
+}
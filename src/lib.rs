@@ -10,9 +10,9 @@
 //! If the character is not found in the phonetic alphabet, it returns `None`.
 //! 
 //! ```rust
-//! assert_eq!(spellout::code('a').map(|c| c.code()), Some("Alpha".to_string()));
-//! assert_eq!(spellout::code('b').map(|c| c.code()), Some("Bravo".to_string()));
-//! assert_eq!(spellout::code('c').map(|c| c.code()), Some("Charlie".to_string()));
+//! assert_eq!(spellout::code('a').map(|c| c.code()), Some("Alpha"));
+//! assert_eq!(spellout::code('b').map(|c| c.code()), Some("Bravo"));
+//! assert_eq!(spellout::code('c').map(|c| c.code()), Some("Charlie"));
 //! ```
 //! 
 //! #### Get the phonetic codes for a word
@@ -20,31 +20,84 @@
 //! ```rust
 //! use spellout::{CodesBuilder, PhoneticCode};
 //! 
-//! let codes = CodesBuilder::build(PhoneticCode::Uk);
-//! assert_eq!(codes.code('a').map(|c| c.code()), Some("Able".to_string()));
-//! assert_eq!(codes.code('b').map(|c| c.code()), Some("Baker".to_string()));
+//! let codes = CodesBuilder::try_build(PhoneticCode::Uk).unwrap();
+//! assert_eq!(codes.code('a').map(|c| c.code()), Some("Able"));
+//! assert_eq!(codes.code('b').map(|c| c.code()), Some("Baker"));
 //! ```
-use std::collections::HashMap;
+use std::borrow::Cow;
 use std::fmt::Display;
-use std::{fs::File, path::Path, str::FromStr, sync::OnceLock};
+use std::{path::Path, str::FromStr, sync::{Arc, OnceLock}};
 
 use clap::ValueEnum;
 
+use index::Index;
+
+mod alphabet;
+mod case;
 mod codes;
+mod decode;
+mod definition;
+mod detect;
+mod diagnostic;
+mod export;
+mod index;
+mod inline;
+mod matching;
+mod metadata;
+mod registry;
+mod separators;
+mod tokenize;
+
+pub use alphabet::PhoneticAlphabet;
+pub use case::CasePolicy;
+pub use decode::{DecodeOptions, DecodeReport, DecodedToken, UnknownToken};
+pub use detect::{best_detection, detect, Detection};
+pub use diagnostic::Diagnostic;
+pub use export::{quote_csv, ExportFormat};
+pub use inline::InlineOptions;
+pub use matching::Matching;
+pub use metadata::Metadata;
+pub use registry::{search_path, Registry};
+use registry::CachedCodes;
+pub use separators::Separators;
 
 #[derive(Debug)]
 pub enum Error {
     Asset(String),
+    /// The cyclic inheritance of the phonetic codes, or the cyclic inclusion of the files, from the first one to the repeated one.
+    Cycle(Vec<String>),
+    Decode(Vec<UnknownToken>),
+    /// The problems found in parsing a code file in the strict mode.
+    Diagnostics(Vec<Diagnostic>),
     FileNotFound(String, Option<std::io::Error>),
     IO(std::io::Error),
+    InvalidCode(String),
     Parse(String),
-    UnknownPhoneticCode(String),
+    /// No phonetic code is detected confidently, with the top candidates of the ranking (see [`best_detection`]).
+    Undetected(Vec<Detection>),
+    /// The unknown name of the phonetic code, and the suggestions of the similar names.
+    UnknownPhoneticCode(String, Vec<String>),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::Asset(name) => write!(f, "{name}: Phonetic code asset not found"),
+            Error::Cycle(chain) => write!(f, "Cyclic inheritance: {}", chain.join(" -> ")),
+            Error::Decode(unknowns) => {
+                write!(f, "Decode error: {} unknown phonetic code(s)", unknowns.len())?;
+                for unknown in unknowns {
+                    write!(f, "\n    {unknown}")?;
+                }
+                Ok(())
+            },
+            Error::Diagnostics(diagnostics) => {
+                write!(f, "Parse error: {} problem(s)", diagnostics.len())?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n    {diagnostic}")?;
+                }
+                Ok(())
+            },
             Error::FileNotFound(path, error) => {
                 if let Some(e) = error {
                     write!(f, "{path}: File not found: {e}")
@@ -53,40 +106,112 @@ impl Display for Error {
                 }
             },
             Error::IO(e) => write!(f, "I/O error: {e}"),
+            Error::InvalidCode(msg) => write!(f, "Invalid phonetic code: {msg}"),
             Error::Parse(msg) => write!(f, "Parse error: {msg}"),
-            Error::UnknownPhoneticCode(name) => write!(f, "{name}: Unknown phonetic code"),
+            Error::Undetected(candidates) => {
+                write!(f, "No phonetic code detected with the confidence of {:.2} or more", Detection::MIN_CONFIDENCE)?;
+                for candidate in candidates {
+                    write!(f, "\n    {candidate}")?;
+                }
+                Ok(())
+            },
+            Error::UnknownPhoneticCode(name, suggestions) => {
+                write!(f, "{name}: Unknown phonetic code")?;
+                match suggestions.as_slice() {
+                    [] => Ok(()),
+                    [only] => write!(f, ", did you mean '{only}'?"),
+                    [init @ .., last] => {
+                        let init = init.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>().join(", ");
+                        write!(f, ", did you mean {init} or '{last}'?")
+                    },
+                }
+            },
         }
     }
 }
 
-static NATO: OnceLock<Codes> = OnceLock::new();
-
 pub fn is_available_name(name: &str) -> bool {
-    codes::is_available_name(name)
+    registry().contains(name)
+}
+
+/// Returns the shared registry, which contains the builtin alphabets, the embedded assets (see [`Registry`]),
+/// and the alphabets registered by [`register`].
+/// The alphabet files in the [`search_path`] are not registered, use [`Registry::with_search_path`] for them.
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::default)
+}
+
+/// Registers the given alphabet into the shared registry (see [`registry`]), and replaces the alphabet of the same name, if any.
+/// The registered alphabet is available by the name everywhere, such as `parse::<Codes>()`, [`codes_of`] with [`PhoneticCode::Asset`],
+/// the `base:` directives of the code files, and [`detect`].
+///
+/// ```rust
+/// use spellout::{Code, Codes, Error, PhoneticAlphabet};
+///
+/// struct Orchard;
+///
+/// impl PhoneticAlphabet for Orchard {
+///     fn name(&self) -> &str {
+///         "orchard"
+///     }
+///
+///     fn codes(&self) -> Result<Vec<Code>, Error> {
+///         Ok(vec![Code::try_new("A", "Apricot")?, Code::try_new("P", "Plum")?])
+///     }
+/// }
+///
+/// spellout::register(Orchard);
+/// let orchard = "Orchard".parse::<Codes>().unwrap();
+/// assert_eq!(orchard.code('p').map(|c| c.code()), Some("Plum"));
+/// ```
+pub fn register<A: PhoneticAlphabet + Send + Sync + 'static>(alphabet: A) {
+    registry().register(alphabet);
 }
 
 /// Returns the phonetic code for a given character.
 /// If the character is not found in the phonetic alphabet, it returns `None`.
 pub fn code(letter: char) -> Option<&'static Code> {
-    NATO.get_or_init(|| {
-        PhoneticCode::of(PhoneticCode::Nato)
-    }).code(letter)
+    codes_of(PhoneticCode::Nato).code(letter)
 }
 
-/// Returns an iterator over the characters in the given string and their corresponding phonetic codes.
-/// The iterator yields a tuple of the character and an `Option<&Code>`, where the
-/// `Option<&Code>` is `Some(&Code)` if the character has a corresponding phonetic code, and `None` otherwise.
-pub fn encode(words: &str) -> impl Iterator<Item = (char, Option<&Code>)> {
-    NATO.get_or_init(|| {
-        PhoneticCode::of(PhoneticCode::Nato)
-    }).encode(words)
+/// Returns an iterator over the spelling units in the given string and their corresponding phonetic codes.
+/// The iterator yields a tuple of the spelling unit and an `Option<&Code>`, where the
+/// `Option<&Code>` is `Some(&Code)` if the unit has a corresponding phonetic code, and `None` otherwise.
+pub fn encode(words: &str) -> impl Iterator<Item = (String, Option<&Code>)> {
+    codes_of(PhoneticCode::Nato).encode(words)
 }
 
 /// Returns an iterator over all the phonetic codes in the NATO alphabet.
 pub fn entries() -> impl Iterator<Item = &'static Code> {
-    NATO.get_or_init(|| {
-        PhoneticCode::of(PhoneticCode::Nato)
-    }).entries()
+    codes_of(PhoneticCode::Nato).entries()
+}
+
+/// Returns the phonetic codes of the given alphabet, which are built at the first call and shared afterwards.
+/// The codes of the builtin variants are cached without the lookup of the registry, unless the alphabet is replaced by [`register`].
+///
+/// # Panics
+///
+/// Panics with the suggestions of the similar names if no alphabet has the name of [`PhoneticCode::Asset`],
+/// or if the alphabet fails to build. Use [`CodesBuilder::try_build`] for the names given at runtime.
+///
+/// ```rust
+/// use spellout::PhoneticCode;
+///
+/// let uk = spellout::codes_of(PhoneticCode::Uk);
+/// assert_eq!(uk.code('a').map(|c| c.code()), Some("Able"));
+/// assert!(std::ptr::eq(uk, spellout::codes_of(PhoneticCode::Uk)));
+/// ```
+pub fn codes_of(code: PhoneticCode) -> &'static Codes {
+    static BUILTINS: OnceLock<Vec<CachedCodes>> = OnceLock::new();
+
+    let variants = PhoneticCode::value_variants();
+    let codes = match variants.iter().position(|variant| *variant == code) {
+        Some(i) => BUILTINS.get_or_init(|| variants.iter().map(|_| CachedCodes::default()).collect())[i]
+            .get(registry(), code.name()),
+        None => registry().codes(code.name()),
+    };
+    codes.unwrap_or_else(|err| panic!("{err}"))
 }
 
 /// Predefined phonetic codes.
@@ -112,112 +237,180 @@ pub enum PhoneticCode {
     Asset(String),
 }
 
-impl Display for PhoneticCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl PhoneticCode {
+    /// Returns the name of the alphabet in the registry, such as "nato".
+    fn name(&self) -> &str {
         match self {
-            PhoneticCode::Chp => write!(f, "chp"),
-            PhoneticCode::English => write!(f, "english"),
-            PhoneticCode::Eu => write!(f, "eu"),
-            PhoneticCode::France => write!(f, "france"),
-            PhoneticCode::International => write!(f, "international"),
-            PhoneticCode::Italia => write!(f, "italia"),
-            PhoneticCode::Nato => write!(f, "nato"),
-            PhoneticCode::Netherlands => write!(f, "netherlands"),
-            PhoneticCode::Philippines => write!(f, "philippines"),
-            PhoneticCode::Sweden => write!(f, "sweden"),
-            PhoneticCode::Uk => write!(f, "uk"),
-            PhoneticCode::USAAirpots => write!(f, "usaairpots"),
-            PhoneticCode::Japanese => write!(f, "japanese"),
-            PhoneticCode::Indonesia => write!(f, "indonesia"),
-            PhoneticCode::Asset(name) => write!(f, "{name}"),
+            PhoneticCode::Chp => "chp",
+            PhoneticCode::English => "english",
+            PhoneticCode::Eu => "eu",
+            PhoneticCode::France => "france",
+            PhoneticCode::International => "international",
+            PhoneticCode::Italia => "italia",
+            PhoneticCode::Nato => "nato",
+            PhoneticCode::Netherlands => "netherlands",
+            PhoneticCode::Philippines => "philippines",
+            PhoneticCode::Sweden => "sweden",
+            PhoneticCode::Uk => "uk",
+            PhoneticCode::USAAirpots => "usaairpots",
+            PhoneticCode::Japanese => "japanese",
+            PhoneticCode::Indonesia => "indonesia",
+            PhoneticCode::Asset(name) => name,
         }
     }
 }
 
+impl Display for PhoneticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 impl FromStr for Codes {
     type Err = Error;
     /// Converts a string into a [`Codes`] struct, by matching the string against the predefined phonetic code names (see [`PhoneticCode`]).
     /// 
-    /// If the string matches a known phonetic code name, it returns a `Codes` struct, and does not match, it returns an error message.
+    /// If the string matches a known phonetic code name or alias in the default registry (see [`registry`]), it returns a `Codes` struct.
     /// The matching is case-insensitive, so for example "nato", "NATO", and "NaTo" would all match the [`PhoneticCode::Nato`] variant.
+    /// The unknown names result in [`Error::UnknownPhoneticCode`] with the suggestions of the similar names.
     fn from_str(s: &str) -> Result<Self, Error> {
-        match s.to_lowercase().as_str() {
-            "chp" => Ok(PhoneticCode::of(PhoneticCode::Chp)),
-            "english" => Ok(PhoneticCode::of(PhoneticCode::English)),
-            "eu" => Ok(PhoneticCode::of(PhoneticCode::Eu)),
-            "france" => Ok(PhoneticCode::of(PhoneticCode::France)),
-            "international" => Ok(PhoneticCode::of(PhoneticCode::International)),
-            "italia" => Ok(PhoneticCode::of(PhoneticCode::Italia)),
-            "nato" => Ok(PhoneticCode::of(PhoneticCode::Nato)),
-            "netherlands" => Ok(PhoneticCode::of(PhoneticCode::Netherlands)),
-            "philippines" => Ok(PhoneticCode::of(PhoneticCode::Philippines)),
-            "sweden" => Ok(PhoneticCode::of(PhoneticCode::Sweden)),
-            "uk" => Ok(PhoneticCode::of(PhoneticCode::Uk)),
-            "usaairpots" => Ok(PhoneticCode::of(PhoneticCode::USAAirpots)),
-            "japanese" => Ok(PhoneticCode::of(PhoneticCode::Japanese)),
-            "indonesia" => Ok(PhoneticCode::of(PhoneticCode::Indonesia)),
-            _ => Ok(PhoneticCode::Asset(s.to_string()).of()),
-        }
+        registry().codes(s).cloned()
     }
 }
 
 impl PhoneticCode {
-    fn of(self) -> Codes{
-        match self {
-            PhoneticCode::Chp => Codes::new_of(PC::Chp(codes::Chp::new()), Vec::new()),
-            PhoneticCode::English => Codes::new_of(PC::English(codes::English::new()), Vec::new()),
-            PhoneticCode::Eu => Codes::new_of(PC::Eu(codes::Eu::new()), Vec::new()),
-            PhoneticCode::France => Codes::new_of(PC::France(codes::France::new()), Vec::new()),
-            PhoneticCode::International => Codes::new_of(PC::International(codes::International::new()), Vec::new()),
-            PhoneticCode::Italia => Codes::new_of(PC::Italia(codes::Italia::new()), Vec::new()),
-            PhoneticCode::Nato => Codes::new_of(PC::Nato(codes::Nato::new()), Vec::new()),
-            PhoneticCode::Netherlands => Codes::new_of(PC::Netherlands(codes::Netherlands::new()), Vec::new()),
-            PhoneticCode::Sweden => Codes::new_of(PC::Sweden(codes::Sweden::new()), Vec::new()),
-            PhoneticCode::Uk => Codes::new_of(PC::Uk(codes::Uk::new()), Vec::new()),
-            PhoneticCode::Japanese => Codes::new_of(PC::Japanese(codes::Japanese::new()), Vec::new()),
-            // The following are custom phonetic codes that are based on the NATO alphabet, but with some substitutions for specific characters.
-            PhoneticCode::USAAirpots => Codes::new_of(PC::Nato(codes::Nato::new()), vec![Code::new('D', "Dixie")]),
-            PhoneticCode::Indonesia => Codes::new_of(PC::Nato(codes::Nato::new()), vec![Code::new('L', "London")]),
-            PhoneticCode::Philippines => Codes::new_of(PC::Nato(codes::Nato::new()), vec![Code::new('H', "Hawk")]),
-            PhoneticCode::Asset(name) => codes::build_from_asset(&name).unwrap_or_else(|err| {
-                log::warn!("Failed to load phonetic code asset '{name}': {err}");
-                Codes::new_of(PC::Null, Vec::new())
-            }),
-        }
-    }
-
+    /// Returns the names of all the alphabets in the default registry (see [`registry`]) in alphabetical order.
     pub fn available_names() -> Vec<String> {
-        let mut entries = codes::list_assets();
-        for pc in PhoneticCode::value_variants() {
-            entries.push(pc.to_string());
-        }
-        entries.sort();
-        entries
+        registry().names().map(String::from).collect()
     }
 }
 
-/// Represents a character and its corresponding phonetic code.
-/// The `Code` struct contains two fields: `alphabet`, which is the character being represented,
-/// and `code`, which is the phonetic code corresponding to that character.
+/// Represents a spelling unit and its corresponding phonetic code.
+/// The `Code` struct contains two fields: `letters`, which is the spelling unit being represented,
+/// and `code`, which is the phonetic code corresponding to that unit.
+/// A spelling unit is usually a single character, but may be a sequence of characters, such as the German "Sch" or the Dutch "IJ".
+/// In addition, a `Code` may have aliases, which are the alternative spellings of the code accepted in decoding, such as "Alfa" for "Alpha".
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Code {
-    /// The character being represented.
-    letter: char,
-    /// The phonetic code corresponding to the character.
-    code: String,
+    /// The spelling unit being represented.
+    letters: Cow<'static, str>,
+    /// The phonetic code corresponding to the spelling unit.
+    code: Cow<'static, str>,
+    /// The alternative spellings of the phonetic code.
+    aliases: Cow<'static, [Cow<'static, str>]>,
 }
 
 impl Code {
-    pub(crate) fn new<S: AsRef<str>>(letter: char, code: S) -> Self {
-        Code { letter, code: code.as_ref().to_string() }
+    pub(crate) fn new<L: Into<String>, S: AsRef<str>>(letters: L, code: S) -> Self {
+        Code { letters: Cow::Owned(letters.into()), code: Cow::Owned(code.as_ref().to_string()), aliases: Cow::Borrowed(&[]) }
+    }
+
+    /// Creates a code from the static data without allocation, for the tables of the builtin alphabets.
+    pub(crate) const fn fixed(letters: &'static str, code: &'static str) -> Self {
+        Code::fixed_with_aliases(letters, code, &[])
+    }
+
+    /// Creates a code with the aliases from the static data without allocation.
+    pub(crate) const fn fixed_with_aliases(letters: &'static str, code: &'static str, aliases: &'static [Cow<'static, str>]) -> Self {
+        Code { letters: Cow::Borrowed(letters), code: Cow::Borrowed(code), aliases: Cow::Borrowed(aliases) }
+    }
+
+    pub(crate) fn with_aliases<S: AsRef<str>>(mut self, aliases: &[S]) -> Self {
+        self.aliases.to_mut().extend(aliases.iter().map(|a| Cow::Owned(a.as_ref().to_string())));
+        self
+    }
+
+    /// Creates a new code of the given spelling unit and the primary spelling.
+    /// The letters must be non-empty without whitespaces and control characters,
+    /// and the spelling must be non-blank without leading or trailing whitespaces, '|', and control characters.
+    /// Otherwise, this function returns [`Error::InvalidCode`].
+    ///
+    /// ```rust
+    /// use spellout::Code;
+    ///
+    /// let sch = Code::try_new("Sch", "Schule").unwrap();
+    /// assert_eq!(sch.letters(), "Sch");
+    /// assert!(Code::try_new("", "Empty").is_err());
+    /// ```
+    pub fn try_new<L: AsRef<str>, S: AsRef<str>>(letters: L, code: S) -> Result<Self, Error> {
+        alphabet::validate_letters(letters.as_ref())?;
+        alphabet::validate_spelling(code.as_ref())?;
+        Ok(Code::new(letters.as_ref(), code))
+    }
+
+    /// Adds the aliases accepted in decoding, and returns the updated code.
+    /// Each alias is validated in the same manner as the spelling of [`Code::try_new`].
+    pub fn try_with_aliases<S: AsRef<str>>(self, aliases: &[S]) -> Result<Self, Error> {
+        for alias in aliases {
+            alphabet::validate_spelling(alias.as_ref())?;
+        }
+        Ok(self.with_aliases(aliases))
     }
 
+    /// Returns the first character of the spelling unit.
+    /// Use [`Code::letters`] to get the whole unit of multi-character codes.
     pub fn letter(&self) -> char {
-        self.letter
+        self.letters.chars().next().unwrap_or_default()
+    }
+
+    /// Returns the spelling unit, such as "A" or "Sch".
+    pub fn letters(&self) -> &str {
+        &self.letters
+    }
+
+    /// Returns the number of characters in the spelling unit.
+    pub fn len(&self) -> usize {
+        self.letters.chars().count()
+    }
+
+    /// Returns `true` if the spelling unit has no characters.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Returns the primary spelling of the phonetic code, such as "Alpha".
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns an iterator over the alternative spellings of the phonetic code, which are accepted in decoding.
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.aliases.iter().map(|alias| alias.as_ref())
     }
 
-    pub fn code(&self) -> String {
-        self.code.to_string()
+    /// Returns the primary spelling of the phonetic code in the case of the given spelling unit.
+    /// If the unit is the lower case (or upper case) form of the letters of this code, the spelling is also converted into the lower case (or upper case).
+    /// Otherwise, this method returns the primary spelling as is.
+    ///
+    /// The case-preserving spellings are decoded into the original case in [`Matching::Tolerant`] mode (see [`Codes::decode_with`]),
+    /// use [`Code::code`] for the canonical spelling.
+    ///
+    /// ```rust
+    /// let hotel = spellout::code('H').unwrap();
+    /// assert_eq!(hotel.spell("H"), "Hotel");
+    /// assert_eq!(hotel.spell("h"), "hotel");
+    /// ```
+    pub fn spell(&self, unit: &str) -> String {
+        if unit == self.letters {
+            self.code.to_string()
+        } else if unit == self.letters.to_lowercase() {
+            self.code.to_lowercase()
+        } else if unit == self.letters.to_uppercase() {
+            self.code.to_uppercase()
+        } else {
+            self.code.to_string()
+        }
+    }
+
+    /// Returns `true` if the given unit is the letters of this code, or its lower case or upper case form.
+    fn is_case_form_of(&self, unit: &str) -> bool {
+        unit == self.letters || unit == self.letters.to_lowercase() || unit == self.letters.to_uppercase()
+    }
+
+    /// Returns an iterator over the primary spelling and the aliases of the phonetic code.
+    pub fn spellings(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.code())
+            .chain(self.aliases())
     }
 }
 
@@ -226,47 +419,105 @@ pub struct CodesBuilder {
 
 impl CodesBuilder {
     /// Creates a new `Codes` struct with the given rule name.
+    /// The struct is cloned from the cache of [`codes_of`], so the builtin tables are not built again.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same manner as [`codes_of`], if no alphabet has the name of [`PhoneticCode::Asset`].
+    #[deprecated(since = "0.1.2", note = "use `CodesBuilder::try_build`, which returns the error instead of panicking")]
     pub fn build(code: PhoneticCode) -> Codes {
-        PhoneticCode::of(code)
+        codes_of(code).clone()
+    }
+
+    /// Creates a new `Codes` struct with the given rule name,
+    /// returns [`Error::UnknownPhoneticCode`] with the suggestions if no alphabet has the name of [`PhoneticCode::Asset`].
+    pub fn try_build(code: PhoneticCode) -> Result<Codes, Error> {
+        registry().codes(&code.to_string()).cloned()
     }
 
     /// Creates a new `Codes` struct with the given base alphabet and substitutions.
-    pub fn build_with(base: PhoneticCode, codes: Vec<Code>) -> Codes {
-        let (pc, codes) = match base {
-            PhoneticCode::Chp => (PC::Chp(codes::Chp::new()), codes),
-            PhoneticCode::English => (PC::English(codes::English::new()), codes),
-            PhoneticCode::Eu => (PC::Eu(codes::Eu::new()), codes),
-            PhoneticCode::France => (PC::France(codes::France::new()), codes),
-            PhoneticCode::International => (PC::International(codes::International::new()), codes),
-            PhoneticCode::Italia => (PC::Italia(codes::Italia::new()), codes),
-            PhoneticCode::Nato => (PC::Nato(codes::Nato::new()), codes),
-            PhoneticCode::Netherlands => (PC::Netherlands(codes::Netherlands::new()), codes),
-            PhoneticCode::Sweden => (PC::Sweden(codes::Sweden::new()), codes),
-            PhoneticCode::Uk => (PC::Uk(codes::Uk::new()), codes),
-            PhoneticCode::Japanese => (PC::Japanese(codes::Japanese::new()), codes),
-            // The following are custom phonetic codes that are based on the NATO alphabet, but with some substitutions for specific characters.
-            PhoneticCode::USAAirpots => (PC::Nato(codes::Nato::new()), codes.into_iter().chain(vec![Code::new('D', "Dixie")]).collect()),
-            PhoneticCode::Indonesia => (PC::Nato(codes::Nato::new()), codes.into_iter().chain(vec![Code::new('L', "London")]).collect()),
-            PhoneticCode::Philippines => (PC::Nato(codes::Nato::new()), codes.into_iter().chain(vec![Code::new('H', "Hawk")]).collect()),            
-            PhoneticCode::Asset(name) => (PC::Null, codes::build_from_asset(&name).unwrap_or_else(|_| Codes::new_of(PC::Null, Vec::new())).codes),
-        };
-        Codes::new_of(pc, codes)
+    /// This function returns the error if the base alphabet is not available (see [`CodesBuilder::try_build`]).
+    pub fn build_with(base: PhoneticCode, codes: Vec<Code>) -> Result<Codes, Error> {
+        let mut result = CodesBuilder::try_build(base)?;
+        result.extend(codes.into_iter().collect());
+        Ok(result)
+    }
+
+    /// Creates a new `Codes` struct from the given alphabet (see [`PhoneticAlphabet`]).
+    /// This function returns [`Error::InvalidCode`] if the alphabet has no codes, or the letters or the spellings are not unique in the alphabet.
+    pub fn build_alphabet<A: PhoneticAlphabet + ?Sized>(alphabet: &A) -> Result<Codes, Error> {
+        let codes = alphabet.codes()?;
+        alphabet::validate(&codes)
+            .map_err(|e| Error::InvalidCode(format!("{}: {e}", alphabet.name())))?;
+        let metadata = alphabet.metadata().with_name(alphabet.name());
+        Ok(codes.into_iter().collect::<Codes>().with_metadata(metadata))
     }
 
     /// Creates a new `Codes` struct by reading phonetic codes from a file.
     /// The file should contain lines in the format "A, Alfa", where the first part is the character and the second part is the phonetic code.
+    /// The first part may be a sequence of characters, such as "Sch, Schule", to define a multi-character spelling unit.
     /// 
     /// The delimiter between the character and the code accepts a comma, semicolon, colon, equals sign, or tab.
     /// Lines that are empty or start with a '#' character are ignored as comments.
     /// 
     /// Note that, the each line must be splitted into two parts by the delimiter, otherwise it will be ignored.
+    /// The invalid lines are skipped with warnings, use [`CodesBuilder::build_from_file_strict`] to find them.
+    ///
+    /// The code part may list the aliases after the primary spelling, separated by '|' characters, such as "J, Juliett | Juliet".
+    /// The primary spelling is used for encoding, and all of spellings are accepted in decoding.
+    /// The header lines starting with `#@` describe the metadata of the alphabet, such as `#@ language: de` (see [`Metadata`]),
+    /// and the name of the metadata is the file name without the extension.
+    ///
+    /// The directive lines compose the alphabet from the others, in order of the lines.
+    /// `base: eu, nato` appends the codes of the given alphabets, the later ones override the earlier ones,
+    /// `include: ./other.txt` appends the codes of the file relative to this file,
+    /// and `remove: Ä Ö Ü` drops the letters appended before.
+    /// The cyclic inheritance or inclusion results in [`Error::Cycle`].
+    ///
+    /// The file may define the alphabet in TOML or JSON, detected by the extension (`.toml` and `.json`) or the content.
+    /// The structured definition has `base` (a name or a list of names), `include`, and `remove` in the same manner as the directives,
+    /// `metadata` in the same keys as the headers, and `codes` of the entries with `letters`, `code`, and optional `aliases` and `note`.
+    /// Unlike the line format, the unknown keys and the duplicated letters are errors.
+    ///
+    /// ```rust
+    /// use spellout::CodesBuilder;
+    ///
+    /// let fruits = CodesBuilder::build_from_file("testdata/fruits.toml").unwrap();
+    /// assert_eq!(fruits.lookup("Ch").map(|c| c.code()), Some("Cherry"));
+    /// assert_eq!(fruits.code('d').map(|c| c.code()), Some("Delta")); // inherited from the base.
+    /// assert_eq!(fruits.metadata().display_name(), Some("Fruits Phonetic Alphabet"));
+    /// ```
+    ///
+    /// The bases are resolved in the shared registry (see [`registry`]), use [`Registry::build_from_file`] for the other registry.
     pub fn build_from_file<P: AsRef<Path>>(path: P) -> Result<Codes, Error> {
-        let file = File::open(path)
-            .map_err(Error::IO)?;
-        codes::build_from_reader(file)
+        registry().build_from_file(path)
+    }
+
+    /// Creates a new `Codes` struct by reading phonetic codes from a file in the same format as [`CodesBuilder::build_from_file`],
+    /// but fails on any problem in the file, such as the line without the delimiter, the invalid escape, the duplicated letters,
+    /// and the unknown metadata key.
+    /// This function returns [`Error::Diagnostics`] with the line and the column of every problem.
+    ///
+    /// ```rust
+    /// use spellout::CodesBuilder;
+    ///
+    /// let codes = CodesBuilder::build_from_file_strict("testdata/custom_code.txt").unwrap();
+    /// assert_eq!(codes.metadata().name(), Some("custom_code"));
+    /// ```
+    pub fn build_from_file_strict<P: AsRef<Path>>(path: P) -> Result<Codes, Error> {
+        registry().build_from_file_strict(path)
     }
 }
 
+/// Names the metadata of the codes after the file name without the extension.
+fn name_after_file(codes: Codes, path: &Path) -> Codes {
+    let metadata = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => codes.metadata().clone().with_name(stem),
+        None => codes.metadata().clone(),
+    };
+    codes.with_metadata(metadata)
+}
+
 /// Represents a collection of phonetic codes.
 /// 
 /// To construct a `Codes` instance, use [`CodesBuilder`] struct,
@@ -280,8 +531,8 @@ impl CodesBuilder {
 /// ```rust
 /// use spellout::{CodesBuilder, PhoneticCode};
 /// 
-/// let nato = CodesBuilder::build(PhoneticCode::Nato);
-/// let uk = CodesBuilder::build(PhoneticCode::Uk);
+/// let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+/// let uk = CodesBuilder::try_build(PhoneticCode::Uk).unwrap();
 /// ```
 /// 
 /// #### Create a `Codes` struct from a file
@@ -301,28 +552,114 @@ impl CodesBuilder {
 /// let eu = "eu".parse::<Codes>()
 ///     .expect("Failed to read phonetic codes from file");
 /// ```
+#[derive(Clone)]
 pub struct Codes {
+    /// The phonetic codes sorted by the letters.
     codes: Vec<Code>,
+    case: CasePolicy,
+    separators: Separators,
+    metadata: Metadata,
+    index: Arc<Index>,
 }
 
 impl Codes {
-    fn new_of(base: PC, codes: Vec<Code>) -> Codes {
-        let mut map = HashMap::new();
-        for code in base.into_entries() {
-            map.insert(code.letter(), code);
-        }
-        for code in codes {
-            map.insert(code.letter(), code);
+    fn from_sorted(codes: Vec<Code>, case: CasePolicy, separators: Separators) -> Codes {
+        let index = Arc::new(Index::build(&codes, case));
+        Codes { codes, case, separators, metadata: Metadata::default(), index }
+    }
+
+    /// Sets the case folding policy for matching the letters, and returns the updated `Codes` struct.
+    /// The default policy is [`CasePolicy::Unicode`].
+    pub fn with_case_policy(mut self, case: CasePolicy) -> Codes {
+        if self.case != case {
+            self.case = case;
+            self.index = Arc::new(Index::build(&self.codes, case));
         }
-        let mut codes = map.into_values().collect::<Vec<_>>();
-        codes.sort_by_key(|a| a.letter());
-        Codes { codes }
+        self
+    }
+
+    /// Returns the case folding policy for matching the letters.
+    pub fn case_policy(&self) -> CasePolicy {
+        self.case
+    }
+
+    /// Sets the words for the word separators and the punctuations, and returns the updated `Codes` struct.
+    /// The default is [`Separators::default`], use [`Separators::none`] to spell no separators.
+    pub fn with_separators(mut self, separators: Separators) -> Codes {
+        self.separators = separators;
+        self
+    }
+
+    /// Returns the words for the word separators and the punctuations.
+    pub fn separators(&self) -> &Separators {
+        &self.separators
+    }
+
+    /// Sets the metadata of the alphabet, and returns the updated `Codes` struct.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Codes {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Returns the metadata of the alphabet, such as the name, the language, and the standard (see [`Metadata`]).
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
     }
 
     /// Returns the phonetic code for a given character.
+    /// The matching follows the case folding policy (see [`CasePolicy`]), so 'ä' finds the code of 'Ä'.
     pub fn code(&self, c: char) -> Option<&Code> {
-        let cc = c.to_ascii_uppercase();
-        self.codes.iter().find(|code| code.letter == cc)
+        let mut buf = [0u8; 4];
+        self.lookup(c.encode_utf8(&mut buf))
+    }
+
+    /// Returns the phonetic code for a given spelling unit, such as "A" or "Sch".
+    /// The matching follows the case folding policy (see [`CasePolicy`]), so "sch", "SCH" and "Sch" find the same code.
+    /// If no letters match, the separators (see [`Separators`]) are consulted for a single character unit.
+    pub fn lookup(&self, letters: &str) -> Option<&Code> {
+        self.get(letters)
+            .or_else(|| self.index.find_folded(letters, self.case).and_then(|letters| self.get(letters)))
+            .or_else(|| self.lookup_separator(letters))
+    }
+
+    /// Returns the code of the identical letters by the binary search.
+    fn get(&self, letters: &str) -> Option<&Code> {
+        self.codes.binary_search_by(|code| code.letters().cmp(letters))
+            .ok()
+            .map(|i| &self.codes[i])
+    }
+
+    fn lookup_separator(&self, letters: &str) -> Option<&Code> {
+        let mut chars = letters.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => self.separators.code(c),
+            _ => None,
+        }
+    }
+
+    /// Splits the given characters into spelling units by matching the longest unit first.
+    /// A multi-character unit matches only in its original, lower case, or upper case form, e.g., "Sch", "sch", or "SCH", but not "sCh".
+    fn split_units(&self, chars: Vec<char>) -> Vec<(String, Option<&Code>)> {
+        let max_len = self.index.max_unit_len();
+        let mut result = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let longest = (2..=max_len.min(chars.len() - i)).rev()
+                .find_map(|len| {
+                    let unit = chars[i..i + len].iter().collect::<String>();
+                    self.lookup(&unit)
+                        .filter(|code| code.is_case_form_of(&unit))
+                        .map(|code| (len, unit, code))
+                });
+            if let Some((len, unit, code)) = longest {
+                result.push((unit, Some(code)));
+                i += len;
+            } else {
+                result.push((chars[i].to_string(), self.code(chars[i])));
+                i += 1;
+            }
+        }
+        result
     }
 
     /// Returns an iterator over all the phonetic codes in the `Codes` struct, including both the base alphabet and any substitutions.
@@ -330,122 +667,388 @@ impl Codes {
         self.codes.iter()
     }
 
+    /// Exports the phonetic codes and the metadata in the given format (see [`ExportFormat`]).
+    /// The exported line format, TOML, and JSON build the same codes by [`CodesBuilder::build_from_file`].
+    /// This method returns [`Error::InvalidCode`] if a spelling is not representable in the line format, such as the spelling with '#'.
+    ///
+    /// ```rust
+    /// use spellout::{Codes, ExportFormat};
+    ///
+    /// let denmark = "denmark".parse::<Codes>().unwrap();
+    /// let toml = denmark.export(ExportFormat::Toml).unwrap();
+    /// assert!(toml.contains("language = \"da\""));
+    /// assert!(toml.contains("code = \"Ægir\""));
+    /// ```
+    pub fn export(&self, format: ExportFormat) -> Result<String, Error> {
+        export::export(self, format)
+    }
+
+    /// Writes the phonetic codes and the metadata in the given format into the writer (see [`Codes::export`]).
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W, format: ExportFormat) -> Result<(), Error> {
+        let exported = self.export(format)?;
+        writer.write_all(exported.as_bytes())
+            .map_err(Error::IO)
+    }
+
     /// Extends the current `Codes` struct with another `Codes` struct, by merging their phonetic codes.
+    /// The codes of `other` override those of the same letters, and the index is updated only for the merged codes.
     /// Note that the ownership of the given `other` is consumed in the process.
     pub fn extend(&mut self, other: Codes) {
-        let mut map = self.codes.iter().map(|c| (c.letter(), c.clone())).collect::<HashMap<_, _>>();
+        let index = Arc::make_mut(&mut self.index);
+        let mut merged = Vec::with_capacity(self.codes.len() + other.codes.len());
+        let mut current = std::mem::take(&mut self.codes).into_iter().peekable();
         for code in other.codes {
-            map.insert(code.letter(), code);
+            while let Some(kept) = current.next_if(|c| c.letters < code.letters) {
+                merged.push(kept);
+            }
+            if let Some(replaced) = current.next_if(|c| c.letters == code.letters) {
+                index.remove(&replaced, self.case);
+            }
+            index.insert(&code, self.case);
+            merged.push(code);
         }
-        let mut codes = map.into_values().collect::<Vec<_>>();
-        codes.sort_by_key(|a| a.letter());
-        self.codes = codes;
+        merged.extend(current);
+        self.codes = merged;
     }
 
     /// Concatenates `self` and `other` and create another `Codes` struct, by merging their phonetic codes.
     pub fn concat(&self, other: &Codes) -> Codes {
-        let mut map = self.codes.iter()
-            .map(|c| (c.letter(), c.clone())).collect::<HashMap<_, _>>();
-        for code in other.codes.iter() {
-            map.insert(code.letter(), code.clone());
-        }
-        let mut codes = map.into_values().collect::<Vec<_>>();
-        codes.sort_by_key(|a| a.letter());
-        Codes { codes }
+        let mut codes = self.clone();
+        codes.extend(other.clone());
+        codes
     }
 
     /// Decodes a list of phonetic codes into a string, by finding the corresponding character for each code.
     /// If a code does not have a corresponding character, it is replaced with a space character in the output string.
+    ///
+    /// The codes must be identical to the spellings (see [`Matching::Exact`]),
+    /// and the words of the separators (see [`Separators`]), such as "Space", are decoded into the corresponding characters.
     pub fn decode(&self, items: Vec<String>) -> String {
-        let mut result = Vec::new();
-        for item in items {
-            match self.codes.iter().find(|c| c.code == item) {
-                Some(code) => result.push(code.letter()),
-                None => result.push(' '),
+        self.decode_with(items, Matching::Exact)
+    }
+
+    /// Decodes a list of phonetic codes into a string in the given matching mode (see [`Matching`]).
+    /// If a code does not have a corresponding character, it is replaced with a space character in the output string.
+    ///
+    /// In [`Matching::Tolerant`] mode, the lower case spellings, such as "hotel", are decoded into the lower case letters.
+    /// Therefore, the case-preserving spellings by [`Code::spell`] are decoded into the original text,
+    /// except the spelling shared by several codes, such as "IJmuiden" of "IJ" and "Ĳ" in netherlands, and "朝日のア" of "あ" and "ア" in japanese.
+    /// The shared spelling is decoded into the first code in the order of the letters ("IJ" and "あ", respectively).
+    ///
+    /// ```rust
+    /// use spellout::{CodesBuilder, Matching, PhoneticCode};
+    ///
+    /// let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+    /// let items = vec![" Hotel ".to_string(), "Xray".to_string(), "Arger".to_string(), "hotel".to_string()];
+    /// assert_eq!(nato.decode_with(items, Matching::Tolerant), "HXÄh");
+    /// ```
+    pub fn decode_with(&self, items: Vec<String>, matching: Matching) -> String {
+        self.decode_report(items, DecodeOptions::default().matching(matching))
+            .map(DecodeReport::into_text)
+            .unwrap_or_default() // never fails in non-strict mode.
+    }
+
+    /// Decodes a list of phonetic codes, and returns the report with the positions of the unrecognized tokens.
+    /// The blank tokens are treated as word breaks, and are not reported as the unrecognized tokens.
+    ///
+    /// In the strict mode (see [`DecodeOptions::strict`]), this method returns [`Error::Decode`] if any token is not recognized.
+    ///
+    /// ```rust
+    /// use spellout::{CodesBuilder, DecodeOptions, PhoneticCode};
+    ///
+    /// let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+    /// let items = vec!["Hotel".to_string(), "".to_string(), "Hotle".to_string()];
+    /// let report = nato.decode_report(items.clone(), DecodeOptions::default()).unwrap();
+    /// assert_eq!(report.text(), "H  ");
+    /// assert_eq!(report.unknowns()[0].index, 2);
+    /// assert_eq!(report.tokens()[0].letters.as_deref(), Some("H"));
+    /// assert_eq!(report.tokens()[2].letters, None);
+    /// assert!(nato.decode_report(items, DecodeOptions::default().strict(true)).is_err());
+    /// ```
+    pub fn decode_report(&self, items: Vec<String>, options: DecodeOptions) -> Result<DecodeReport, Error> {
+        let mut result = String::new();
+        let mut tokens = Vec::new();
+        let mut unknowns = Vec::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let letters = match self.decode_item(&item, options.matching_mode()) {
+                Some(letters) => Some(letters),
+                None if item.trim().is_empty() => Some(" ".to_string()),
+                None => {
+                    unknowns.push(UnknownToken { index, text: item.clone() });
+                    None
+                },
+            };
+            result.push_str(letters.as_deref().unwrap_or(" "));
+            tokens.push(DecodedToken { index, text: item, letters });
+        }
+        if options.is_strict() && !unknowns.is_empty() {
+            Err(Error::Decode(unknowns))
+        } else {
+            Ok(DecodeReport::new(result, tokens, unknowns))
+        }
+    }
+
+    /// Splits the free-form text into the phonetic codes, by matching the pieces of the text against the known spellings.
+    ///
+    /// The text is split into pieces by whitespaces and hyphens, then, the longest run of pieces matching a known spelling is taken greedily,
+    /// so that the codes containing spaces or hyphens, such as "Umlaut Aachen" and "X-ray", are kept as single codes.
+    /// A piece without spaces, such as "朝日のアいろはのイ", is segmented by the longest known spellings, if possible.
+    /// The pieces which match no spelling are returned as is, and will be reported as unknown tokens by [`Codes::decode_report`].
+    ///
+    /// ```rust
+    /// use spellout::{Matching, Codes};
+    ///
+    /// let din5009 = "din5009".parse::<Codes>().unwrap();
+    /// let tokens = din5009.tokenize("Umlaut Aachen Rostock Goslar Essen Rostock", Matching::Exact);
+    /// assert_eq!(tokens, vec!["Umlaut Aachen", "Rostock", "Goslar", "Essen", "Rostock"]);
+    /// assert_eq!(din5009.decode(tokens), "ÄRGER");
+    /// ```
+    pub fn tokenize(&self, text: &str, matching: Matching) -> Vec<String> {
+        let pieces = tokenize::pieces(text);
+        let max_pieces = self.separators.entries()
+            .flat_map(Code::spellings)
+            .map(tokenize::count_pieces)
+            .fold(self.index.max_pieces(), usize::max);
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < pieces.len() {
+            let longest = (1..=max_pieces.min(pieces.len() - i)).rev()
+                .map(|len| (len, tokenize::join(&pieces[i..i + len])))
+                .find(|(_, candidate)| self.decode_item(candidate, matching).is_some());
+            if let Some((len, token)) = longest {
+                tokens.push(token);
+                i += len;
+            } else {
+                let piece = pieces[i].text;
+                match self.segment(piece) {
+                    Some(segments) => tokens.extend(segments),
+                    None => tokens.push(piece.to_string()),
+                }
+                i += 1;
             }
         }
-        result.into_iter().collect()
+        tokens
     }
 
-    /// Converts a string into an iterator of characters and their corresponding phonetic codes.
+    /// Splits the single line spelling by [`Codes::spell_inline`] into the phonetic codes, with the blank tokens between the words.
+    /// The codes in a word are split by [`Codes::tokenize`] if the letter separator is whitespaces, and the unknown markers are left as the unrecognized tokens.
+    ///
+    /// ```rust
+    /// use spellout::{Codes, InlineOptions, Matching};
+    ///
+    /// let nato = "nato".parse::<Codes>().unwrap();
+    /// let options = InlineOptions::default().letter_separator("-").word_separator(" ");
+    /// let tokens = nato.tokenize_inline("Hotel-Echo Whiskey", &options, Matching::Exact);
+    /// assert_eq!(tokens, vec!["Hotel", "Echo", "", "Whiskey"]);
+    /// assert_eq!(nato.decode(tokens), "HE W");
+    /// ```
+    pub fn tokenize_inline(&self, text: &str, options: &InlineOptions, matching: Matching) -> Vec<String> {
+        inline::tokenize(self, text, options, matching)
+    }
+
+    /// Segments the given piece into the known spellings by matching the longest prefix, returns `None` if the segmentation fails.
+    /// The prefixes longer than the longest spelling are not tried, so that the long piece is segmented in linear time.
+    fn segment(&self, piece: &str) -> Option<Vec<String>> {
+        let max_len = self.separators.entries()
+            .flat_map(Code::spellings)
+            .map(|spelling| spelling.chars().count())
+            .fold(self.index.max_spelling_len(), usize::max);
+        let mut segments = Vec::new();
+        let mut rest = piece;
+        while !rest.is_empty() {
+            let ends = rest.char_indices()
+                .map(|(i, c)| i + c.len_utf8())
+                .take(max_len)
+                .collect::<Vec<_>>();
+            let prefix = ends.into_iter().rev()
+                .map(|end| &rest[..end])
+                .find(|prefix| self.decode_item(prefix, Matching::Exact).is_some())?;
+            segments.push(prefix.to_string());
+            rest = &rest[prefix.len()..];
+        }
+        Some(segments)
+    }
+
+    /// Decodes the given item into the letters.
+    /// The lower case (or upper case) spelling of a code is decoded into the lower case (or upper case) letters in both modes,
+    /// see [`Code::spell`].
+    fn decode_item(&self, item: &str, matching: Matching) -> Option<String> {
+        if let Some(letters) = self.index.find_spelling(item) {
+            return Some(letters.to_string());
+        }
+        if let Some((letters, form)) = self.index.find_case_form(item) {
+            return Some(form.apply(letters));
+        }
+        self.find_separator(item, matching)
+            .or_else(|| self.find_code(item, matching))
+            .map(|code| code.letters.to_string())
+    }
+
+    /// Returns the separator code whose word matches the given item in the given matching mode.
+    pub(crate) fn find_separator(&self, item: &str, matching: Matching) -> Option<&Code> {
+        self.separators.entries()
+            .find(|code| code.spellings().any(|spelling| matching.matches(self.case, item, spelling)))
+    }
+
+    /// Returns the phonetic code entry that matches the given item in the given matching mode (see [`Matching::matches`]).
+    /// The item is compared with both the primary spelling and the aliases of each code.
+    /// The identical code is preferred to the codes matched in [`Matching::Tolerant`] mode.
+    pub fn find_code(&self, item: &str, matching: Matching) -> Option<&Code> {
+        let found = match matching {
+            Matching::Exact => self.index.find_spelling(item)
+                .or_else(|| self.index.find_case_form(item).map(|(letters, _)| letters)),
+            Matching::Tolerant => self.index.find_spelling(item)
+                .or_else(|| self.index.find_tolerant(item, self.case)),
+        };
+        found.and_then(|letters| self.get(letters))
+    }
+
+    /// Spells the words in a single line, such as "Hotel Echo / Whiskey Oscar", in the layout of the given options.
+    /// The whitespaces are the word breaks, and the characters without the phonetic code are replaced with the unknown marker.
+    ///
+    /// ```rust
+    /// use spellout::{Codes, InlineOptions};
+    ///
+    /// let nato = "nato".parse::<Codes>().unwrap();
+    /// assert_eq!(nato.spell_inline("HI YOU", &InlineOptions::default()), "Hotel India / Yankee Oscar Uniform");
+    /// assert_eq!(nato.spell_inline("A€B", &InlineOptions::default().letter_separator(", ")), "Alpha, ?, Bravo");
+    /// ```
+    pub fn spell_inline(&self, words: &str, options: &InlineOptions) -> String {
+        inline::spell(self, words, options)
+    }
+
+    /// Converts a string into an iterator of spelling units and their corresponding phonetic codes.
+    /// The longest spelling unit is matched first, so "Schule" yields "Sch" rather than "S", if the alphabet has a code for "Sch".
     #[cfg(not(feature = "normalization"))]
-    pub fn encode(&self, words: &str) -> impl Iterator<Item = (char, Option<&Code>)> {
-        words.chars().map(move |c| (c, self.code(c)))
+    pub fn encode(&self, words: &str) -> impl Iterator<Item = (String, Option<&Code>)> {
+        self.split_units(words.chars().collect()).into_iter()
     }
 
-    /// Converts a string into an iterator of characters and their corresponding phonetic codes.
+    /// Converts a string into an iterator of spelling units and their corresponding phonetic codes.
+    /// The longest spelling unit is matched first, so "Schule" yields "Sch" rather than "S", if the alphabet has a code for "Sch".
     /// The method normalizes the input string using [Unicode Normalization](https://crates.io/crates/unicode-normalization) Form C (NFC) before processing,
     /// which can be useful for handling characters that can be represented in multiple ways in Unicode.
     #[cfg(feature = "normalization")]
-    pub fn encode(&self, words: &str) -> impl Iterator<Item = (char, Option<&Code>)> {
+    pub fn encode(&self, words: &str) -> impl Iterator<Item = (String, Option<&Code>)> {
         use unicode_normalization::UnicodeNormalization;
-        words.nfc().map(move |c| (c, self.code(c)))
+        self.split_units(words.nfc().collect()).into_iter()
     }
 }
 
-enum PC {
-    Chp(codes::Chp),
-    English(codes::English),
-    Eu(codes::Eu),
-    France(codes::France),
-    International(codes::International),
-    Italia(codes::Italia),
-    Japanese(codes::Japanese),
-    Nato(codes::Nato),
-    Netherlands(codes::Netherlands),
-    Sweden(codes::Sweden),
-    Uk(codes::Uk),
-    Null,
+impl std::fmt::Debug for Codes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Codes")
+            .field("codes", &self.codes)
+            .field("case", &self.case)
+            .field("separators", &self.separators)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
 }
 
-impl PC {
-    fn into_entries(self) -> Box<dyn Iterator<Item = Code>> {
-        match self {
-            PC::Chp(alphabet) => Box::new(alphabet.into_entries()),
-            PC::English(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Eu(alphabet) => Box::new(alphabet.into_entries()),
-            PC::France(alphabet) => Box::new(alphabet.into_entries()),
-            PC::International(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Italia(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Japanese(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Nato(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Netherlands(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Sweden(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Uk(alphabet) => Box::new(alphabet.into_entries()),
-            PC::Null => Box::new([].into_iter()),
-        }
+impl PartialEq for Codes {
+    /// Compares the phonetic codes, the case folding policy, and the separators, regardless of the metadata.
+    fn eq(&self, other: &Self) -> bool {
+        self.codes == other.codes && self.case == other.case && self.separators == other.separators
+    }
+}
+
+impl IntoIterator for Codes {
+    type Item = Code;
+    type IntoIter = std::vec::IntoIter<Code>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.codes.into_iter()
     }
 }
 
-trait PhoneticAlphabet {
-    fn into_entries(self) -> impl Iterator<Item = Code>;
+impl<'a> IntoIterator for &'a Codes {
+    type Item = &'a Code;
+    type IntoIter = std::slice::Iter<'a, Code>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.codes.iter()
+    }
+}
+
+impl FromIterator<Code> for Codes {
+    /// Collects the phonetic codes into a `Codes` struct, the later code overrides the earlier one of the same letters.
+    fn from_iter<I: IntoIterator<Item = Code>>(iter: I) -> Self {
+        let mut codes = iter.into_iter().collect::<Vec<_>>();
+        // the stable sort of the reversed codes puts the later one first among the same letters, which the dedup keeps.
+        codes.reverse();
+        codes.sort_by(|a, b| a.letters.cmp(&b.letters));
+        codes.dedup_by(|a, b| a.letters == b.letters);
+        Codes::from_sorted(codes, CasePolicy::default(), Separators::default())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_code() {
-        assert_eq!(crate::code('a').map(|c| c.code()), Some("Alpha".to_string()));
-        assert_eq!(crate::code('b').map(|c| c.code()), Some("Bravo".to_string()));
-        assert_eq!(crate::code('c').map(|c| c.code()), Some("Charlie".to_string()));
+        assert_eq!(crate::code('a').map(|c| c.code()), Some("Alpha"));
+        assert_eq!(crate::code('b').map(|c| c.code()), Some("Bravo"));
+        assert_eq!(crate::code('c').map(|c| c.code()), Some("Charlie"));
     }
 
     #[test]
     fn test_nato() {
-        let usa_airpots = CodesBuilder::build(PhoneticCode::Nato);
-        assert_eq!(crate::code('a').map(|c| c.code()), Some("Alpha".to_string()));
-        assert_eq!(crate::code('b').map(|c| c.code()), Some("Bravo".to_string()));
-        assert_eq!(crate::code('c').map(|c| c.code()), Some("Charlie".to_string()));
+        let usa_airpots = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        assert_eq!(crate::code('a').map(|c| c.code()), Some("Alpha"));
+        assert_eq!(crate::code('b').map(|c| c.code()), Some("Bravo"));
+        assert_eq!(crate::code('c').map(|c| c.code()), Some("Charlie"));
         assert_eq!(usa_airpots.entries().count(), 39);
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_codes_of() {
+        assert!(std::ptr::eq(codes_of(PhoneticCode::Nato), codes_of(PhoneticCode::Nato)));
+        let denmark = codes_of(PhoneticCode::Asset("Denmark".to_string()));
+        assert!(std::ptr::eq(denmark, codes_of(PhoneticCode::Asset("denmark".to_string()))));
+        assert_eq!(denmark.code('Å').map(|c| c.code()), Some("Åse"));
+        assert_eq!(CodesBuilder::build(PhoneticCode::Uk), *codes_of(PhoneticCode::Uk));
+        assert!(matches!(CodesBuilder::try_build(PhoneticCode::Asset("denmrk".to_string())), Err(Error::UnknownPhoneticCode(_, _))));
+    }
+
+    #[test]
+    #[should_panic(expected = "denmrk: Unknown phonetic code, did you mean 'denmark'?")]
+    fn test_codes_of_unknown() {
+        codes_of(PhoneticCode::Asset("denmrk".to_string()));
+    }
+
+    struct Grove;
+
+    impl PhoneticAlphabet for Grove {
+        fn name(&self) -> &str {
+            "grove"
+        }
+
+        fn codes(&self) -> Result<Vec<Code>, Error> {
+            Ok(vec![Code::try_new("O", "Olive")?, Code::try_new("Q", "Quince")?])
+        }
+    }
+
+    #[test]
+    fn test_register_shared() {
+        crate::register(Grove);
+        assert_eq!(codes_of(PhoneticCode::Asset("Grove".to_string())).code('q').map(|c| c.code()), Some("Quince"));
+        let detected = crate::detect("Olive Quince", Matching::Exact);
+        assert_eq!(detected[0].name(), "grove");
+        assert_eq!(detected[0].codes().unwrap().entries().count(), 2);
+        let based = codes::build_from_reader("base: grove\nP, Pear".as_bytes()).unwrap();
+        assert_eq!(based.decode(vec!["Olive".to_string(), "Pear".to_string()]), "OP");
+    }
+
     #[test]
     fn test_predefined() {
-        let usa_airpots = CodesBuilder::build(PhoneticCode::USAAirpots);
-        assert_eq!(usa_airpots.code('D').map(|c| c.code()), Some("Dixie".to_string()));
-        assert_eq!(usa_airpots.code('A').map(|c| c.code()), Some("Alpha".to_string()));
+        let usa_airpots = CodesBuilder::try_build(PhoneticCode::USAAirpots).unwrap();
+        assert_eq!(usa_airpots.code('D').map(|c| c.code()), Some("Dixie"));
+        assert_eq!(usa_airpots.code('A').map(|c| c.code()), Some("Alpha"));
         assert_eq!(usa_airpots.entries().count(), 39);
     }
 
@@ -453,8 +1056,250 @@ mod tests {
     fn test_from_file() {
         let codes = CodesBuilder::build_from_file("testdata/custom_code.txt")
             .expect("Failed to read phonetic codes from file");
-        assert_eq!(codes.code('A').map(|c| c.code()), Some("Arctic".to_string()));
-        assert_eq!(codes.code('B').map(|c| c.code()), Some("Bishop".to_string()));
+        assert_eq!(codes.code('A').map(|c| c.code()), Some("Arctic"));
+        assert_eq!(codes.code('B').map(|c| c.code()), Some("Bishop"));
         assert_eq!(codes.entries().count(), 26);
     }
+
+    #[test]
+    fn test_case_folding() {
+        let denmark = "denmark".parse::<Codes>().unwrap();
+        let items = denmark.encode("åse")
+            .map(|(_, code)| code.map(|c| c.code()))
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![Some("Åse"), Some("Sierra"), Some("Echo")]);
+        assert_eq!(crate::code('ö').map(|c| c.code()), Some("Öser"));
+
+        let din5009 = "din5009".parse::<Codes>().unwrap();
+        assert_eq!(din5009.code('ß').map(|c| c.code()), Some("Eszett"));
+
+        let ascii = CodesBuilder::try_build(PhoneticCode::Sweden).unwrap().with_case_policy(CasePolicy::Ascii);
+        assert!(ascii.code('ä').is_none());
+        assert!(ascii.code('a').is_some());
+    }
+
+    #[test]
+    fn test_turkish_case_policy() {
+        let codes = codes::build_from_reader("I, Isparta\n\\u{0130}, İzmir\n\\u{03A3}, Sigma".as_bytes()).unwrap();
+        assert_eq!(codes.code('i').map(|c| c.code()), Some("Isparta"));
+        assert_eq!(codes.code('ς').map(|c| c.code()), Some("Sigma"));
+        let turkish = codes.with_case_policy(CasePolicy::Turkish);
+        assert_eq!(turkish.code('i').map(|c| c.code()), Some("İzmir"));
+        assert_eq!(turkish.code('ı').map(|c| c.code()), Some("Isparta"));
+    }
+
+    #[test]
+    fn test_decode_aliases() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let items = vec!["Alfa", "Alpha", "Juliet", "Juliett"].into_iter().map(String::from).collect();
+        assert_eq!(nato.decode(items), "AAJJ");
+        assert_eq!(nato.code('J').map(|c| c.code()), Some("Juliett"));
+    }
+
+    #[test]
+    fn test_encode_digraphs() {
+        let codes = "din5009_1983".parse::<Codes>().unwrap();
+        assert_eq!(codes.lookup("sch").map(|c| c.code()), Some("Schule"));
+        let units = codes.encode("Schach").map(|(unit, _)| unit).collect::<Vec<_>>();
+        assert_eq!(units, vec!["Sch", "a", "ch"]);
+        let items = codes.encode("Schach")
+            .map(|(_, code)| code.map(|c| c.code().to_string()).unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec!["Schule", "Anton", "Charlotte"]);
+        assert_eq!(codes.decode(items), "SchACh");
+    }
+
+    #[test]
+    fn test_encode_longest_match_fallback() {
+        let codes = CodesBuilder::try_build(PhoneticCode::Netherlands).unwrap();
+        let units = codes.encode("ijs").map(|(unit, code)| (unit, code.map(|c| c.code()))).collect::<Vec<_>>();
+        assert_eq!(units[0], ("ij".to_string(), Some("IJmuiden")));
+        assert_eq!(units[1], ("s".to_string(), Some("Simon")));
+    }
+
+    #[test]
+    fn test_japanese_kana() {
+        let japanese = CodesBuilder::try_build(PhoneticCode::Japanese).unwrap();
+        let items = japanese.encode("コーヒー")
+            .map(|(_, code)| code.map(|c| c.code().to_string()).unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec!["子どものコ", "長音", "飛行機のヒ", "長音"]);
+        assert_eq!(japanese.decode(items), "こーひー");
+        assert!(japanese.encode("ガスパンきゃ").all(|(_, code)| code.is_some()));
+    }
+
+    #[test]
+    fn test_japanese_decode_variants() {
+        let japanese = CodesBuilder::try_build(PhoneticCode::Japanese).unwrap();
+        let items = vec!["朝日", "あさひのあ", "アサヒ", "為替に濁点", "ハガキノハニハンダクテン", "小さい大和"];
+        assert_eq!(japanese.decode(items.into_iter().map(String::from).collect()), "あああがぱゃ");
+        let tokens = japanese.tokenize("さくらのさ ゆみや 為替のカに濁点あさひ", Matching::Exact);
+        assert_eq!(japanese.decode(tokens), "さゆがあ");
+    }
+
+    #[test]
+    fn test_collect_and_iterate() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let collected = nato.clone().into_iter().collect::<Codes>();
+        assert_eq!(collected, nato);
+        assert_eq!((&nato).into_iter().count(), 39);
+
+        let mut extended = nato.clone();
+        extended.extend(vec![Code::new('D', "Dixie"), Code::new("Ch", "Charlie Hotel")].into_iter().collect());
+        assert_eq!(extended.code('d').map(|c| c.code()), Some("Dixie"));
+        assert_eq!(extended.lookup("ch").map(|c| c.code()), Some("Charlie Hotel"));
+        assert_eq!(extended.entries().count(), 40);
+        assert_ne!(extended, nato);
+        assert_eq!(nato.concat(&extended), extended);
+    }
+
+    #[test]
+    fn test_extend_updates_index() {
+        let mut codes = vec![Code::new("X", "Same"), Code::new("A", "Alpha"), Code::new("Y", "Same"), Code::new("A", "Able")]
+            .into_iter()
+            .collect::<Codes>();
+        assert_eq!(codes.entries().map(Code::code).collect::<Vec<_>>(), vec!["Able", "Same", "Same"]);
+        assert_eq!(codes.decode(vec!["Same".to_string(), "Alpha".to_string()]), "X ");
+
+        codes.extend(vec![Code::new("X", "Xray"), Code::new("B", "Bravo")].into_iter().collect());
+        assert_eq!(codes.entries().map(Code::letters).collect::<Vec<_>>(), vec!["A", "B", "X", "Y"]);
+        let items = ["Same", "Xray", "bravo", "Able"].map(String::from).to_vec();
+        assert_eq!(codes.decode_with(items, Matching::Tolerant), "YXbA");
+        assert_eq!(codes.lookup("x").map(Code::code), Some("Xray"));
+        assert_eq!(codes, codes.entries().cloned().collect::<Codes>());
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let tokens = nato.tokenize("Hotel Echo Lima Lima Oscar", Matching::Exact);
+        assert_eq!(nato.decode(tokens), "HELLO");
+        let tokens = nato.tokenize("X-ray Space x-ray Xray-Yankee HotelEcho Hotle", Matching::Exact);
+        assert_eq!(tokens, vec!["X-ray", "Space", "x-ray", "Xray", "Yankee", "Hotel", "Echo", "Hotle"]);
+        assert_eq!(nato.decode(tokens), "X xXYHE ");
+        let tokens = nato.tokenize("X ray hotel", Matching::Tolerant);
+        assert_eq!(tokens, vec!["X ray", "hotel"]);
+    }
+
+    #[test]
+    fn test_tokenize_long_piece() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let text = "Hotel".repeat(2000);
+        let start = std::time::Instant::now();
+        assert_eq!(nato.tokenize(&text, Matching::Exact).len(), 2000);
+        assert_eq!(nato.tokenize(&format!("{text}Hotle"), Matching::Tolerant).len(), 1);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5), "took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_decode_case_and_separators() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let items = nato.encode("Hi, X!")
+            .map(|(unit, code)| code.map(|c| c.spell(&unit)).unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec!["Hotel", "india", "Comma", "Space", "X-ray", "Exclamation"]);
+        assert_eq!(nato.decode_with(items, Matching::Tolerant), "Hi, X!");
+
+        let none = CodesBuilder::try_build(PhoneticCode::Nato).unwrap().with_separators(Separators::none());
+        assert!(none.code(' ').is_none());
+    }
+
+    #[test]
+    fn test_decode_exact_case() {
+        let nato = CodesBuilder::try_build(PhoneticCode::Nato).unwrap();
+        let items = vec!["Hotel", "hotel", "HOTEL", "hOTEL", "space", "Space"].into_iter().map(String::from).collect::<Vec<_>>();
+        let report = nato.decode_report(items.clone(), DecodeOptions::default()).unwrap();
+        assert_eq!(report.text(), "HhH   ");
+        assert_eq!(report.unknowns().iter().map(|u| u.index).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(nato.decode_with(items, Matching::Tolerant), "HhHH  ");
+    }
+
+    #[test]
+    fn test_decode_agrees_with_matches() {
+        let din5009 = "din5009".parse::<Codes>().unwrap();
+        let items = ["Anton", "anton", "ANTON", " Anton ", "Umlaut Aachen", "Umlaut-Aachen", "umlautaachen", "Antonia", "Space", "space", "X"];
+        for matching in [Matching::Exact, Matching::Tolerant] {
+            for item in items {
+                let expected = din5009.entries().chain(din5009.separators().entries())
+                    .find(|code| code.spellings().any(|spelling| matching.matches(din5009.case, item, spelling)));
+                let found = din5009.find_code(item, matching).or_else(|| din5009.find_separator(item, matching));
+                assert_eq!(found, expected, "{item} in {matching:?}");
+                assert_eq!(din5009.decode_item(item, matching).is_some(), expected.is_some(), "{item} in {matching:?}");
+            }
+        }
+    }
+
+    /// Returns the spelling units of the given codes, including their lower case and upper case forms.
+    fn round_trip_units(codes: &Codes) -> Vec<String> {
+        let mut units = Vec::new();
+        for code in codes.entries() {
+            for unit in [code.letters.to_string(), code.letters.to_lowercase(), code.letters.to_uppercase()] {
+                if !units.contains(&unit) && codes.lookup(&unit) == Some(code) {
+                    units.push(unit);
+                }
+            }
+        }
+        units.extend(codes.separators().entries().map(|c| c.letters.to_string()));
+        units
+    }
+
+    /// Returns the text decoded from the case-preserving spelling of the unit.
+    /// The spelling shared by several codes (e.g., "IJ" and "Ĳ" in netherlands) is decoded into the first code in the order of the letters,
+    /// in the case of the unit.
+    fn round_trip_text(codes: &Codes, unit: &str, code: Option<&Code>) -> String {
+        let Some(code) = code else {
+            return " ".to_string();
+        };
+        let spelling = code.code.to_lowercase();
+        let first = codes.entries().find(|c| c.code.to_lowercase() == spelling).unwrap_or(code);
+        if unit == code.letters {
+            first.letters.to_string()
+        } else if unit == code.letters.to_lowercase() {
+            first.letters.to_lowercase()
+        } else {
+            first.letters.to_uppercase()
+        }
+    }
+
+    #[test]
+    fn test_shared_spellings() {
+        let netherlands = CodesBuilder::try_build(PhoneticCode::Netherlands).unwrap();
+        let items = netherlands.encode("Ĳĳ").map(|(unit, code)| code.unwrap().spell(&unit)).collect::<Vec<_>>();
+        assert_eq!(items, vec!["IJmuiden", "ijmuiden"]);
+        assert_eq!(netherlands.decode_with(items, Matching::Tolerant), "IJij");
+
+        let japanese = CodesBuilder::try_build(PhoneticCode::Japanese).unwrap();
+        let items = japanese.encode("アあ").map(|(unit, code)| code.unwrap().spell(&unit)).collect::<Vec<_>>();
+        assert_eq!(items, vec!["朝日のア", "朝日のア"]);
+        assert_eq!(japanese.decode(items), "ああ");
+    }
+
+    fn all_alphabets() -> &'static Vec<(String, Codes, Vec<String>)> {
+        static ALPHABETS: OnceLock<Vec<(String, Codes, Vec<String>)>> = OnceLock::new();
+        ALPHABETS.get_or_init(|| {
+            PhoneticCode::available_names().into_iter()
+                .map(|name| {
+                    let codes = name.parse::<Codes>().unwrap();
+                    let units = round_trip_units(&codes);
+                    (name, codes, units)
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip_all_alphabets(indexes in proptest::collection::vec(any::<prop::sample::Index>(), 0..32)) {
+            for (name, codes, units) in all_alphabets() {
+                let input = indexes.iter().map(|i| i.get(units).as_str()).collect::<String>();
+                let mut items = Vec::new();
+                let mut expected = String::new();
+                for (unit, code) in codes.encode(&input) {
+                    items.push(code.map(|c| c.spell(&unit)).unwrap_or_default());
+                    expected.push_str(&round_trip_text(codes, &unit, code));
+                }
+                prop_assert_eq!(codes.decode_with(items, Matching::Tolerant), expected, "alphabet: {}", name);
+            }
+        }
+    }
 }
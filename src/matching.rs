@@ -0,0 +1,104 @@
+use clap::ValueEnum;
+
+use crate::CasePolicy;
+
+/// Matching modes for finding the phonetic codes in decoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Matching {
+    /// The given item must be identical to the phonetic code, or its lower case or upper case form,
+    /// which is decoded into the letters in that case (see [`Code::spell`](crate::Code::spell)).
+    /// For example, "hotel" and "HOTEL" match "Hotel", and are decoded into "h" and "H", respectively.
+    #[default]
+    Exact,
+    /// The given item is compared with the phonetic code ignoring the case, whitespaces, hyphens, and diacritics.
+    /// For example, " hotel ", "Xray", and "Arger" match "Hotel", "X-ray", and "Ärger", respectively.
+    Tolerant,
+}
+
+impl Matching {
+    /// Returns `true` if the given item matches the given phonetic code in this mode.
+    /// The case of letters is folded by the given case policy in [`Matching::Tolerant`] mode.
+    /// Decoding (e.g., [`Codes::decode_with`](crate::Codes::decode_with)) recognizes the item if it matches a spelling of a code or a separator in this manner.
+    pub fn matches(&self, case: CasePolicy, item: &str, code: &str) -> bool {
+        match self {
+            Matching::Exact => item == code || item == code.to_lowercase() || item == code.to_uppercase(),
+            Matching::Tolerant => item == code || normalize(case, item) == normalize(case, code),
+        }
+    }
+}
+
+/// Normalizes the given string for [`Matching::Tolerant`] mode.
+/// The normalization folds the case, removes whitespaces and hyphens, and strips the diacritics.
+pub(crate) fn normalize(case: CasePolicy, s: &str) -> String {
+    case.fold(s).chars()
+        .filter(|c| !c.is_whitespace() && !is_hyphen(*c))
+        .flat_map(strip_diacritics)
+        .collect()
+}
+
+fn is_hyphen(c: char) -> bool {
+    matches!(c, '-' | '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2212}')
+}
+
+/// Strips the diacritics from the given character by decomposing it with [Unicode Normalization](https://crates.io/crates/unicode-normalization) Form D (NFD).
+#[cfg(feature = "normalization")]
+fn strip_diacritics(c: char) -> impl Iterator<Item = char> {
+    use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+    std::iter::once(c).nfd().filter(|c| !is_combining_mark(*c))
+}
+
+/// Strips the diacritics from the given upper case character.
+/// Without `normalization` feature, this function handles the Latin letters in Latin-1 Supplement and Latin Extended-A blocks.
+#[cfg(not(feature = "normalization"))]
+fn strip_diacritics(c: char) -> impl Iterator<Item = char> {
+    let stripped = match c {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' => 'A',
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => 'C',
+        'Ď' => 'D',
+        'È'..='Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => 'E',
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => 'G',
+        'Ĥ' => 'H',
+        'Ì'..='Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => 'I',
+        'Ĵ' => 'J',
+        'Ķ' => 'K',
+        'Ĺ' | 'Ļ' | 'Ľ' => 'L',
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => 'N',
+        'Ò'..='Ö' | 'Ō' | 'Ŏ' | 'Ő' => 'O',
+        'Ŕ' | 'Ŗ' | 'Ř' => 'R',
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => 'S',
+        'Ţ' | 'Ť' => 'T',
+        'Ù'..='Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => 'U',
+        'Ŵ' => 'W',
+        'Ý' | 'Ŷ' | 'Ÿ' => 'Y',
+        'Ź' | 'Ż' | 'Ž' => 'Z',
+        c => c,
+    };
+    std::iter::once(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(CasePolicy::Unicode, " Hotel "), "HOTEL");
+        assert_eq!(normalize(CasePolicy::Unicode, "X-ray"), "XRAY");
+        assert_eq!(normalize(CasePolicy::Unicode, "Umlaut Aachen"), "UMLAUTAACHEN");
+        assert_eq!(normalize(CasePolicy::Unicode, "ärger"), "ARGER");
+        assert_eq!(normalize(CasePolicy::Unicode, "Quotiënt"), "QUOTIENT");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Matching::Exact.matches(CasePolicy::Unicode, "Hotel", "Hotel"));
+        assert!(Matching::Exact.matches(CasePolicy::Unicode, "hotel", "Hotel"));
+        assert!(Matching::Exact.matches(CasePolicy::Unicode, "HOTEL", "Hotel"));
+        assert!(!Matching::Exact.matches(CasePolicy::Unicode, "hOTEL", "Hotel"));
+        assert!(!Matching::Exact.matches(CasePolicy::Unicode, " Hotel ", "Hotel"));
+        assert!(Matching::Tolerant.matches(CasePolicy::Unicode, "hotel", "Hotel"));
+        assert!(Matching::Tolerant.matches(CasePolicy::Unicode, "Xray", "X-ray"));
+        assert!(Matching::Tolerant.matches(CasePolicy::Unicode, "Arger", "Ärger"));
+        assert!(!Matching::Tolerant.matches(CasePolicy::Unicode, "Hotels", "Hotel"));
+    }
+}
@@ -0,0 +1,163 @@
+/// Represents the descriptive information of a phonetic alphabet, such as the language and the standard it follows.
+///
+/// The code files describe the metadata in the header lines starting with `#@`, such as `#@ language: de`.
+/// The header lines are comments for the readers not knowing the syntax.
+/// The available keys are `display-name`, `language`, `region`, `standard`, `year`, and `source`.
+///
+/// ```rust
+/// use spellout::Codes;
+///
+/// let din5009 = "din5009".parse::<Codes>().unwrap();
+/// let metadata = din5009.metadata();
+/// assert_eq!(metadata.name(), Some("din5009"));
+/// assert_eq!(metadata.language(), Some("de"));
+/// assert_eq!(metadata.standard(), Some("DIN 5009"));
+/// assert_eq!(metadata.year(), Some(2022));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    name: Option<String>,
+    display_name: Option<String>,
+    language: Option<String>,
+    region: Option<String>,
+    standard: Option<String>,
+    year: Option<u16>,
+    source: Option<String>,
+}
+
+impl Metadata {
+    /// Sets the name of the alphabet for looking up, such as "nato".
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the human readable name of the alphabet, such as "NATO Phonetic Alphabet".
+    pub fn with_display_name<S: Into<String>>(mut self, display_name: S) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    /// Sets the language of the spellings, in the language tag, such as "en" and "de".
+    pub fn with_language<S: Into<String>>(mut self, language: S) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Sets the region using the alphabet, such as "DE" and "CH".
+    pub fn with_region<S: Into<String>>(mut self, region: S) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Sets the standard defining the alphabet, such as "DIN 5009".
+    pub fn with_standard<S: Into<String>>(mut self, standard: S) -> Self {
+        self.standard = Some(standard.into());
+        self
+    }
+
+    /// Sets the year of the standard or the publication.
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    /// Sets the source of the alphabet, such as the URL of the reference.
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Returns the name of the alphabet for looking up.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the human readable name of the alphabet.
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    /// Returns the language of the spellings.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Returns the region using the alphabet.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Returns the standard defining the alphabet.
+    pub fn standard(&self) -> Option<&str> {
+        self.standard.as_deref()
+    }
+
+    /// Returns the year of the standard or the publication.
+    pub fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    /// Returns the source of the alphabet.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Sets the value of the given header key, returns the error if the key is unknown or the value is invalid.
+    pub(crate) fn set(&mut self, key: &str, value: &str) -> Result<(), HeaderError> {
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "display-name" => self.display_name = Some(value.to_string()),
+            "language" => self.language = Some(value.to_string()),
+            "region" => self.region = Some(value.to_string()),
+            "standard" => self.standard = Some(value.to_string()),
+            "year" => self.year = Some(value.parse().map_err(|_| HeaderError::InvalidValue("a year in digits"))?),
+            "source" => self.source = Some(value.to_string()),
+            _ => return Err(HeaderError::UnknownKey),
+        }
+        Ok(())
+    }
+}
+
+/// The error in parsing the header line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HeaderError {
+    UnknownKey,
+    /// The value is invalid, with the description of the expected value.
+    InvalidValue(&'static str),
+}
+
+impl HeaderError {
+    /// Returns the description of what was expected.
+    pub(crate) fn expected(&self) -> &'static str {
+        match self {
+            HeaderError::UnknownKey => "a metadata key (display-name, language, region, standard, year, or source)",
+            HeaderError::InvalidValue(expected) => expected,
+        }
+    }
+}
+
+/// Parses the header line of the code file, such as `#@ language: de`, into the key and the value.
+pub(crate) fn parse_header(line: &str) -> Option<(&str, &str)> {
+    line.trim()
+        .strip_prefix("#@")
+        .and_then(|header| header.split_once(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(parse_header("#@ language: de"), Some((" language", " de")));
+        assert_eq!(parse_header("#@ source: https://example.com"), Some((" source", " https://example.com")));
+        assert_eq!(parse_header("# refs. https://example.com"), None);
+
+        let mut metadata = Metadata::default();
+        assert!(metadata.set(" Year", " 1983").is_ok());
+        assert_eq!(metadata.set("year", "unknown"), Err(HeaderError::InvalidValue("a year in digits")));
+        assert_eq!(metadata.set("author", "someone"), Err(HeaderError::UnknownKey));
+        assert_eq!(metadata.year(), Some(1983));
+    }
+}
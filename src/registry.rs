@@ -0,0 +1,512 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock, RwLockReadGuard};
+
+use crate::{alphabet, codes, detect, Codes, Detection, Error, Matching, Metadata, PhoneticAlphabet};
+
+/// The maximum number of the suggestions for the unknown names.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The id of the next registry, to distinguish the alphabets of the same name in the different registries.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The ids of the registries and the names of the alphabets being built in this thread, from the outermost.
+    static BUILDING: RefCell<Vec<(usize, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the directories of the alphabet files in order of the precedence,
+/// the directories in `$SPELLOUT_PATH` (separated in the manner of `PATH`), `$XDG_DATA_HOME/spellout/codes`
+/// (`~/.local/share/spellout/codes` if `XDG_DATA_HOME` is not set), and `/usr/share/spellout/codes`.
+///
+/// [`Registry::with_search_path`] registers the files in these directories over the builtin alphabets and the embedded assets.
+pub fn search_path() -> Vec<PathBuf> {
+    let mut dirs = std::env::var_os("SPELLOUT_PATH")
+        .map(|paths| std::env::split_paths(&paths).filter(|path| !path.as_os_str().is_empty()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")));
+    dirs.extend(data_home.map(|dir| dir.join("spellout").join("codes")));
+    dirs.push(PathBuf::from("/usr/share/spellout/codes"));
+    dirs
+}
+
+/// Represents a collection of phonetic alphabets, which are looked up by their names or aliases ignoring the case.
+///
+/// The default registry contains the builtin alphabets (see [`PhoneticCode`](crate::PhoneticCode)) and the embedded assets.
+/// The alphabet files in the [`search_path`] are registered by [`Registry::with_search_path`] on demand.
+/// Each alphabet is built into [`Codes`] at the first lookup, and the built codes are cached in the registry.
+/// The registry is shared between threads, and the alphabets are registered through `&self`, such as into the shared registry by [`register`](crate::register).
+/// The replaced alphabets and their codes are kept until the registry is dropped, so that the borrowed codes stay valid after the alphabet is replaced.
+///
+/// ```rust
+/// use spellout::{Code, Error, PhoneticAlphabet, Registry};
+///
+/// struct Fruits;
+///
+/// impl PhoneticAlphabet for Fruits {
+///     fn name(&self) -> &str {
+///         "fruits"
+///     }
+///
+///     fn aliases(&self) -> &[&str] {
+///         &["fruit"]
+///     }
+///
+///     fn codes(&self) -> Result<Vec<Code>, Error> {
+///         Ok(vec![Code::try_new("A", "Apple")?, Code::try_new("B", "Banana")?])
+///     }
+/// }
+///
+/// let registry = Registry::default();
+/// registry.register(Fruits);
+/// assert_eq!(registry.resolve("Fruit"), Some("fruits"));
+/// assert_eq!(registry.codes("fruits").unwrap().code('a').map(|c| c.code()), Some("Apple"));
+/// assert!(registry.names().any(|name| name == "nato"));
+/// ```
+pub struct Registry {
+    id: usize,
+    inner: RwLock<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// The entries sorted by the names.
+    entries: Vec<Arc<Entry>>,
+    /// lower case names and aliases -> the index of the entry.
+    names: HashMap<String, usize>,
+    /// The replaced entries, which are kept until the registry is dropped, since their codes may be borrowed.
+    replaced: Vec<Arc<Entry>>,
+}
+
+struct Entry {
+    alphabet: Box<dyn PhoneticAlphabet + Send + Sync>,
+    /// The registered alphabets are validated in building, while the builtin alphabets and the assets are trusted.
+    validate: bool,
+    codes: OnceLock<Codes>,
+    /// `true` if the entry is replaced by the alphabet of the same name, which invalidates [`CachedCodes`].
+    replaced: AtomicBool,
+}
+
+/// The cache of the codes of an alphabet in the shared registry, which looks up the name at the first call only.
+/// Once the alphabet is replaced, the codes are looked up in the registry at every call.
+#[derive(Default)]
+pub(crate) struct CachedCodes(OnceLock<&'static Entry>);
+
+impl CachedCodes {
+    pub(crate) fn get(&self, registry: &'static Registry, name: &str) -> Result<&'static Codes, Error> {
+        if let Some(entry) = self.0.get()
+            && !entry.replaced.load(Ordering::Acquire)
+            && let Some(codes) = entry.codes.get() {
+            return Ok(codes);
+        }
+        let codes = registry.codes(name)?;
+        if let Some(entry) = registry.entry(name) {
+            let _ = self.0.set(entry);
+        }
+        Ok(codes)
+    }
+}
+
+impl Default for Registry {
+    /// Returns the registry of the builtin alphabets and the embedded assets.
+    fn default() -> Self {
+        let registry = Registry::new();
+        for alphabet in codes::builtins() {
+            registry.insert(alphabet, false);
+        }
+        for asset in codes::assets() {
+            registry.insert(Box::new(asset), false);
+        }
+        registry
+    }
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Registry { id: NEXT_ID.fetch_add(1, Ordering::Relaxed), inner: RwLock::default() }
+    }
+
+    /// Registers the given alphabet, and replaces the alphabet of the same name, if any.
+    /// The codes of the alphabet are validated in the same manner as [`CodesBuilder::build_alphabet`](crate::CodesBuilder::build_alphabet),
+    /// when the alphabet is looked up by [`Registry::codes`].
+    pub fn register<A: PhoneticAlphabet + Send + Sync + 'static>(&self, alphabet: A) {
+        self.insert(Box::new(alphabet), true);
+    }
+
+    /// Registers the alphabet files (`.txt`, `.toml`, and `.json`) in the given directory, named after the file stems,
+    /// and returns the number of the registered files.
+    /// The files replace the alphabets of the same names, and are built in the same manner as [`CodesBuilder::build_from_file`](crate::CodesBuilder::build_from_file).
+    /// The bases of the files are resolved in the same manner as [`Registry::with_search_path`] of the directory.
+    pub fn register_dir<P: AsRef<Path>>(&self, dir: P) -> Result<usize, Error> {
+        let files = codes::user_files(&[dir.as_ref().to_path_buf()])?;
+        let count = files.len();
+        for file in files {
+            self.insert(Box::new(file), false);
+        }
+        Ok(count)
+    }
+
+    /// Registers the alphabet files in the given directories, such as [`search_path`], the earlier directories take precedence over the later ones.
+    /// The missing directories are skipped, and the unreadable ones are skipped with warnings.
+    ///
+    /// A base name of a file (`base:` directive) is resolved to the file of the name in the same directory and the later ones, except the file itself,
+    /// and then to the alphabet of the shared registry (see [`registry`](crate::registry)).
+    /// So, a file may override the alphabet of the same name in the lower layer, such as `nato.txt` of `base: nato` over the builtin NATO alphabet.
+    ///
+    /// ```rust
+    /// use spellout::Registry;
+    ///
+    /// let registry = Registry::default().with_search_path(["testdata/layers/upper", "testdata/layers/lower"]);
+    /// let nato = registry.codes("nato").unwrap();
+    /// assert_eq!(nato.code('a').map(|c| c.code()), Some("Apple"));  // upper/nato.txt
+    /// assert_eq!(nato.code('b').map(|c| c.code()), Some("Banana")); // lower/nato.txt
+    /// assert_eq!(nato.code('c').map(|c| c.code()), Some("Charlie")); // the builtin alphabet
+    /// ```
+    pub fn with_search_path<I: IntoIterator<Item = P>, P: AsRef<Path>>(self, dirs: I) -> Self {
+        let dirs = dirs.into_iter().map(|dir| dir.as_ref().to_path_buf()).collect::<Vec<_>>();
+        for i in (0..dirs.len()).rev() {
+            match codes::user_files(&dirs[i..]) {
+                Ok(files) => files.into_iter().for_each(|file| self.insert(Box::new(file), false)),
+                Err(Error::IO(e)) if e.kind() != std::io::ErrorKind::NotFound => log::warn!("{}: failed to read the alphabet files: {e}", dirs[i].display()),
+                Err(_) => {},
+            }
+        }
+        self
+    }
+
+    /// Builds the codes from the definition file in the same manner as [`CodesBuilder::build_from_file`](crate::CodesBuilder::build_from_file),
+    /// but resolves the bases in this registry, such as the alphabet files of [`Registry::with_search_path`].
+    ///
+    /// ```rust
+    /// use spellout::Registry;
+    ///
+    /// let registry = Registry::default().with_search_path(["testdata/layers/lower"]);
+    /// let smoothie = registry.build_from_file("testdata/layers/smoothie.txt").unwrap(); // base: berries
+    /// assert_eq!(smoothie.code('b').map(|c| c.code()), Some("Blueberry"));
+    /// assert!(Registry::default().build_from_file("testdata/layers/smoothie.txt").is_err());
+    /// ```
+    pub fn build_from_file<P: AsRef<Path>>(&self, path: P) -> Result<Codes, Error> {
+        let path = path.as_ref();
+        codes::build_from_path(path, false, self)
+            .map(|codes| crate::name_after_file(codes, path))
+    }
+
+    /// Builds the codes from the definition file in the same manner as [`CodesBuilder::build_from_file_strict`](crate::CodesBuilder::build_from_file_strict),
+    /// but resolves the bases in this registry.
+    pub fn build_from_file_strict<P: AsRef<Path>>(&self, path: P) -> Result<Codes, Error> {
+        let path = path.as_ref();
+        codes::build_from_path(path, true, self)
+            .map(|codes| crate::name_after_file(codes, path))
+    }
+
+    fn insert(&self, alphabet: Box<dyn PhoneticAlphabet + Send + Sync>, validate: bool) {
+        let entry = Arc::new(Entry { alphabet, validate, codes: OnceLock::new(), replaced: AtomicBool::new(false) });
+        let mut inner = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        match inner.entries.binary_search_by(|e| e.alphabet.name().cmp(entry.alphabet.name())) {
+            Ok(i) => {
+                let replaced = std::mem::replace(&mut inner.entries[i], entry);
+                replaced.replaced.store(true, Ordering::Release);
+                inner.replaced.push(replaced);
+            },
+            Err(i) => inner.entries.insert(i, entry),
+        }
+        inner.reindex();
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Inner> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Borrows the entry for the lifetime of this registry, beyond the lock.
+    fn borrow(&self, entry: &Arc<Entry>) -> &Entry {
+        // SAFETY: the entries are never dropped before the registry, since the replaced ones are moved into `Inner::replaced`,
+        // and the content of `Arc` does not move, so the entry outlives the borrow of the registry.
+        unsafe { &*Arc::as_ptr(entry) }
+    }
+
+    /// Returns the entry of the given name or alias, the lock is released before building the codes of the entry.
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        let inner = self.read();
+        inner.names.get(&name.to_lowercase())
+            .map(|&i| self.borrow(&inner.entries[i]))
+    }
+
+    /// Returns the snapshot of the entries in alphabetical order of the names.
+    fn entries(&self) -> Vec<&Entry> {
+        self.read().entries.iter()
+            .map(|entry| self.borrow(entry))
+            .collect()
+    }
+
+    /// Returns `true` if the registry has the alphabet of the given name or alias.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    /// Returns the name of the alphabet of the given name or alias.
+    pub fn resolve(&self, name: &str) -> Option<&str> {
+        self.entry(name).map(|entry| entry.alphabet.name())
+    }
+
+    /// Returns the alphabet of the given name or alias.
+    pub fn alphabet(&self, name: &str) -> Option<&dyn PhoneticAlphabet> {
+        self.entry(name).map(|entry| entry.alphabet.as_ref() as &dyn PhoneticAlphabet)
+    }
+
+    /// Returns the phonetic codes of the alphabet of the given name or alias, which are built at the first call and cached afterwards.
+    /// This method returns [`Error::UnknownPhoneticCode`] if no alphabet has the given name,
+    /// and the error in building the codes, such as [`Error::InvalidCode`].
+    /// The alphabets inheriting each other, such as the code files of `base: pong` named "ping" and of `base: ping` named "pong", result in [`Error::Cycle`].
+    pub fn codes(&self, name: &str) -> Result<&Codes, Error> {
+        let entry = self.entry(name)
+            .ok_or_else(|| Error::UnknownPhoneticCode(name.to_string(), self.suggest(name)))?;
+        if let Some(codes) = entry.codes.get() {
+            return Ok(codes);
+        }
+        let _building = Building::enter(self.id, entry.alphabet.name())?;
+        let codes = entry.alphabet.codes()?;
+        if entry.validate {
+            alphabet::validate(&codes)
+                .map_err(|e| Error::InvalidCode(format!("{}: {e}", entry.alphabet.name())))?;
+        }
+        let metadata = entry.alphabet.metadata().with_name(entry.alphabet.name());
+        Ok(entry.codes.get_or_init(|| codes.into_iter().collect::<Codes>().with_metadata(metadata)))
+    }
+
+    /// Returns the metadata of the alphabet of the given name or alias, without building the codes.
+    pub fn metadata(&self, name: &str) -> Option<Metadata> {
+        self.entry(name)
+            .map(|entry| entry.alphabet.metadata().with_name(entry.alphabet.name()))
+    }
+
+    /// Returns the names and the aliases similar to the given name, for suggesting them on the unknown names.
+    /// The candidates within the edit distance of a third of the length, or starting with the given name, are returned in order of the similarity.
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        let threshold = (name.chars().count() / 3).max(1);
+        let mut candidates = self.entries().into_iter()
+            .flat_map(|entry| std::iter::once(entry.alphabet.name()).chain(entry.alphabet.aliases().iter().copied()))
+            .filter_map(|candidate| {
+                let distance = strsim::levenshtein(&name, &candidate.to_lowercase());
+                (distance <= threshold || candidate.to_lowercase().starts_with(&name))
+                    .then(|| (distance, candidate.to_string()))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates.into_iter()
+            .map(|(_, candidate)| candidate)
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Returns an iterator over the names of the alphabets in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries().into_iter().map(|entry| entry.alphabet.name())
+    }
+
+    /// Returns an iterator over the alphabets in alphabetical order of the names.
+    pub fn alphabets(&self) -> impl Iterator<Item = &dyn PhoneticAlphabet> {
+        self.entries().into_iter().map(|entry| entry.alphabet.as_ref() as &dyn PhoneticAlphabet)
+    }
+
+    /// Detects the phonetic code of the given spelled text among the alphabets in this registry (see [`detect`](crate::detect)).
+    pub fn detect(&self, text: &str, matching: Matching) -> Vec<Detection> {
+        detect::detect_in(self, text, matching)
+    }
+}
+
+impl Inner {
+    /// Rebuilds the index of the names, the names are preferred to the aliases of the other alphabets.
+    fn reindex(&mut self) {
+        self.names.clear();
+        for (i, entry) in self.entries.iter().enumerate() {
+            for alias in entry.alphabet.aliases() {
+                self.names.insert(alias.to_lowercase(), i);
+            }
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            self.names.insert(entry.alphabet.name().to_lowercase(), i);
+        }
+    }
+}
+
+/// Marks the alphabet as being built until dropped, to detect the cyclic inheritance.
+struct Building;
+
+impl Building {
+    fn enter(id: usize, name: &str) -> Result<Building, Error> {
+        BUILDING.with_borrow_mut(|building| {
+            if let Some(i) = building.iter().position(|(i, n)| *i == id && n == name) {
+                let chain = building[i..].iter().map(|(_, n)| n.clone()).chain([name.to_string()]).collect();
+                return Err(Error::Cycle(chain));
+            }
+            building.push((id, name.to_string()));
+            Ok(Building)
+        })
+    }
+}
+
+impl Drop for Building {
+    fn drop(&mut self) {
+        BUILDING.with_borrow_mut(|building| building.pop());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    struct Broken;
+
+    impl PhoneticAlphabet for Broken {
+        fn name(&self) -> &str {
+            "nato"
+        }
+
+        fn aliases(&self) -> &[&str] {
+            &["broken"]
+        }
+
+        fn codes(&self) -> Result<Vec<Code>, Error> {
+            Ok(vec![Code::try_new("A", "Alpha")?, Code::try_new("B", "Alpha")?])
+        }
+    }
+
+    static LOOPS: OnceLock<Registry> = OnceLock::new();
+
+    /// The alphabet inheriting itself through the other alphabet.
+    struct Loop(&'static str, &'static str);
+
+    impl PhoneticAlphabet for Loop {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn codes(&self) -> Result<Vec<Code>, Error> {
+            let base = LOOPS.get().unwrap().codes(self.1)?;
+            Ok(base.entries().cloned().collect())
+        }
+    }
+
+    #[test]
+    fn test_default_registry() {
+        let registry = Registry::default();
+        assert!(registry.contains("NATO"));
+        assert!(registry.contains("denmark"));
+        assert_eq!(registry.resolve("usaairports"), Some("usaairpots"));
+        assert_eq!(registry.codes("usaairports").unwrap().code('d').map(|c| c.code()), Some("Dixie"));
+        assert!(std::ptr::eq(registry.codes("uk").unwrap(), registry.codes("UK").unwrap()));
+        assert!(matches!(registry.codes("unknown"), Err(Error::UnknownPhoneticCode(_, _))));
+        let names = registry.names().collect::<Vec<_>>();
+        assert!(names.is_sorted());
+        assert_eq!(names.len(), 20);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let registry = Registry::default();
+        assert_eq!(registry.suggest("netherland"), vec!["netherlands"]);
+        assert_eq!(registry.suggest("usaairport"), vec!["usaairports", "usaairpots"]);
+        assert_eq!(registry.suggest("din"), vec!["din5009", "din5009_1983"]);
+        assert!(registry.suggest("klingon").is_empty());
+        let error = "Netherland".parse::<Codes>().unwrap_err();
+        assert_eq!(error.to_string(), "Netherland: Unknown phonetic code, did you mean 'netherlands'?");
+    }
+
+    #[test]
+    fn test_cyclic_inheritance() {
+        let registry = Registry::new();
+        registry.register(Loop("ping", "pong"));
+        registry.register(Loop("pong", "ping"));
+        let registry = LOOPS.get_or_init(|| registry);
+        let error = registry.codes("ping").unwrap_err();
+        assert_eq!(error.to_string(), "Cyclic inheritance: ping -> pong -> ping");
+        assert!(matches!(registry.codes("pong"), Err(Error::Cycle(_))));
+    }
+
+    #[test]
+    fn test_register_dir() {
+        let registry = Registry::default();
+        assert_eq!(registry.register_dir("testdata/include").unwrap(), 3);
+        assert_eq!(registry.resolve("BERRIES"), Some("berries"));
+        let berries = registry.codes("berries").unwrap();
+        assert_eq!(berries.code('b').map(|c| c.code()), Some("Berry"));
+        assert_eq!(berries.metadata().name(), Some("berries"));
+        assert!(matches!(registry.codes("ping"), Err(Error::Cycle(_))));
+        assert!(matches!(registry.register_dir("testdata/missing"), Err(Error::IO(_))));
+    }
+
+    #[test]
+    fn test_search_path() {
+        let registry = Registry::default().with_search_path(["testdata/layers/upper", "testdata/missing", "testdata/layers/lower"]);
+        let nato = registry.codes("nato").unwrap();
+        assert_eq!(nato.code('a').map(|c| c.code()), Some("Apple"));
+        assert_eq!(nato.code('b').map(|c| c.code()), Some("Banana"));
+        assert_eq!(nato.code('c').map(|c| c.code()), Some("Charlie"));
+        assert_eq!(registry.codes("fruits").unwrap().code('c').map(|c| c.code()), Some("Cherry")); // upper/fruits.txt over lower/fruits.txt
+        assert_eq!(registry.codes("berries").unwrap().code('b').map(|c| c.code()), Some("Blueberry")); // lower/berries.txt
+        assert_eq!(registry.codes("uk").unwrap(), Registry::default().codes("uk").unwrap());
+        assert!(matches!(registry.codes("ping"), Err(Error::Cycle(_))));
+
+        // the shared registry has no files of the search path.
+        assert_eq!("nato".parse::<Codes>().unwrap().code('a').map(|c| c.code()), Some("Alpha"));
+        assert!(!crate::registry().contains("fruits"));
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let registry = Registry::default();
+        let count = registry.names().count();
+        let nato = registry.codes("nato").unwrap();
+        registry.register(Broken);
+        assert_eq!(nato.code('a').map(|c| c.code()), Some("Alpha")); // the replaced codes are still valid.
+        assert_eq!(registry.names().count(), count);
+        assert_eq!(registry.resolve("broken"), Some("nato"));
+        assert!(matches!(registry.codes("nato"), Err(Error::InvalidCode(_))));
+    }
+
+    #[test]
+    fn test_cached_codes() {
+        let registry: &'static Registry = Box::leak(Box::default());
+        let cache = CachedCodes::default();
+        let nato = cache.get(registry, "nato").unwrap();
+        assert!(std::ptr::eq(nato, cache.get(registry, "nato").unwrap()));
+        registry.register(Broken);
+        assert!(matches!(cache.get(registry, "nato"), Err(Error::InvalidCode(_)))); // the replaced entry is not cached.
+    }
+
+    /// The alphabet holding a counter, to find whether the registry drops it.
+    struct Counted {
+        _counter: Arc<()>,
+    }
+
+    impl PhoneticAlphabet for Counted {
+        fn name(&self) -> &str {
+            "counted"
+        }
+
+        fn codes(&self) -> Result<Vec<Code>, Error> {
+            Ok(vec![Code::try_new("A", "Alpha")?])
+        }
+    }
+
+    #[test]
+    fn test_drop_registry() {
+        let counter = Arc::new(());
+        let registry = Registry::new();
+        registry.register(Counted { _counter: Arc::clone(&counter) });
+        let codes = registry.codes("counted").unwrap();
+        registry.register(Counted { _counter: Arc::clone(&counter) });
+        assert_eq!(codes.entries().count(), 1); // the replaced codes are still valid.
+        assert_eq!(Arc::strong_count(&counter), 3);
+        drop(registry);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
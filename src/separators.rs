@@ -0,0 +1,69 @@
+use crate::Code;
+
+/// Represents the words for spelling the word separators and punctuations, such as "Space" for ' ' and "Stop" for '.'.
+///
+/// The separators are consulted after the letters of the alphabet, both in encoding and decoding,
+/// so that the encoded codes are decoded into the original text without losing the spaces and the punctuations.
+///
+/// ```rust
+/// use spellout::{CodesBuilder, PhoneticCode, Separators};
+///
+/// let codes = CodesBuilder::try_build(PhoneticCode::Nato).unwrap()
+///     .with_separators(Separators::default().with('_', "Underscore"));
+/// assert_eq!(codes.code(' ').map(|c| c.code()), Some("Space"));
+/// assert_eq!(codes.code('_').map(|c| c.code()), Some("Underscore"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Separators {
+    codes: Vec<Code>,
+}
+
+impl Default for Separators {
+    /// Returns the default separators, "Space", "Break" (new line), "Stop" (full stop), and some punctuations.
+    fn default() -> Self {
+        Separators {
+            codes: vec![
+                Code::new(' ', "Space"),
+                Code::new('\n', "Break"),
+                Code::new('.', "Stop"),
+                Code::new(',', "Comma"),
+                Code::new('?', "Query"),
+                Code::new('!', "Exclamation"),
+                Code::new('-', "Hyphen"),
+                Code::new('/', "Slash"),
+                Code::new(':', "Colon"),
+                Code::new('\'', "Apostrophe"),
+            ],
+        }
+    }
+}
+
+impl Separators {
+    /// Returns an empty separators, which spells no word separators and punctuations.
+    pub fn none() -> Self {
+        Separators { codes: Vec::new() }
+    }
+
+    /// Adds (or replaces) the word for the given character.
+    pub fn with<S: AsRef<str>>(mut self, c: char, word: S) -> Self {
+        self.codes.retain(|code| code.letter() != c);
+        self.codes.push(Code::new(c, word));
+        self
+    }
+
+    /// Removes the word for the given character.
+    pub fn without(mut self, c: char) -> Self {
+        self.codes.retain(|code| code.letter() != c);
+        self
+    }
+
+    /// Returns the separator code for the given character.
+    pub fn code(&self, c: char) -> Option<&Code> {
+        self.codes.iter().find(|code| code.letter() == c)
+    }
+
+    /// Returns an iterator over all the separator codes.
+    pub fn entries(&self) -> impl Iterator<Item = &Code> {
+        self.codes.iter()
+    }
+}
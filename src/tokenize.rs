@@ -0,0 +1,56 @@
+/// Represents a piece of the free-form text, which is a word separated by whitespaces or hyphens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Piece<'a> {
+    /// The text of the piece.
+    pub(crate) text: &'a str,
+    /// The delimiter before the piece, `None` for the first piece.
+    pub(crate) delimiter: Option<char>,
+}
+
+/// Splits the given text into pieces by whitespaces and hyphens.
+/// Consecutive whitespaces are treated as a single space delimiter.
+pub(crate) fn pieces(text: &str) -> Vec<Piece<'_>> {
+    let mut result = Vec::new();
+    for (i, word) in text.split_whitespace().enumerate() {
+        let delimiter = if i == 0 { None } else { Some(' ') };
+        for (j, part) in word.split('-').enumerate() {
+            let delimiter = if j == 0 { delimiter } else { Some('-') };
+            if !part.is_empty() {
+                result.push(Piece { text: part, delimiter });
+            }
+        }
+    }
+    result
+}
+
+/// Joins the given pieces with their delimiters.
+pub(crate) fn join(pieces: &[Piece<'_>]) -> String {
+    let mut result = String::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        if i > 0 {
+            result.extend(piece.delimiter);
+        }
+        result.push_str(piece.text);
+    }
+    result
+}
+
+/// Returns the number of pieces in the given text.
+pub(crate) fn count_pieces(text: &str) -> usize {
+    pieces(text).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pieces() {
+        let p = pieces("  Hotel  X-ray\tUmlaut Aachen ");
+        let texts = p.iter().map(|p| p.text).collect::<Vec<_>>();
+        assert_eq!(texts, vec!["Hotel", "X", "ray", "Umlaut", "Aachen"]);
+        assert_eq!(join(&p[1..3]), "X-ray");
+        assert_eq!(join(&p[3..5]), "Umlaut Aachen");
+        assert_eq!(count_pieces("Umlaut Aachen"), 2);
+    }
+}